@@ -0,0 +1,31 @@
+// Compare precompiled `Pointer` with parsing path string by `pathto()`,
+// and the `/` chain with plain `get()` of serde_json as baseline.
+// cargo bench --bench pointer
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use json_ops::{ValuePath, Pointer, path};
+use serde_json::json;
+
+static PORT: Pointer = Pointer::from_static(&["service", "1", "host", "port"], &[None, Some(1), None, None]);
+
+fn bench_pointer(c: &mut Criterion) {
+    let v = json!({"service": [
+        {"name": "serv_1", "host": {"ip": "127.0.0.1", "port": 8080}},
+        {"name": "serv_2", "host": {"ip": "127.0.0.2", "port": 8081}}
+    ]});
+    let path = "/service/1/host/port";
+    let ptr = Pointer::new(path);
+
+    c.bench_function("pathto", |b| b.iter(|| v.pathto(black_box(path)) | 0));
+    c.bench_function("baseline_get", |b| b.iter(|| {
+        black_box(&v).get("service").and_then(|s| s.get(1)).and_then(|s| s.get("host"))
+            .and_then(|h| h.get("port")).and_then(|p| p.as_i64()).unwrap_or(0)
+    }));
+    c.bench_function("path_chain", |b| b.iter(|| (v.path() / "service" / 1 / "host" / "port") | 0));
+    c.bench_function("pointer", |b| b.iter(|| (v.path() / black_box(&ptr)) | 0));
+    c.bench_function("static_pointer", |b| b.iter(|| PORT.resolve(black_box(&v)) | 0));
+    c.bench_function("path_macro", |b| b.iter(|| (black_box(&v).path() / path!("/service/1/host/port")) | 0));
+}
+
+criterion_group!(benches, bench_pointer);
+criterion_main!(benches);
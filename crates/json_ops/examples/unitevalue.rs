@@ -97,6 +97,39 @@ impl ValuePath for Value {
             _ => None
         }
     }
+
+    /// Get length of sequence part.
+    fn get_len(&self) -> usize
+    {
+        match self {
+            Value::Table(t) => t.seq.len(),
+            _ => 0
+        }
+    }
+
+    /// Get mutable items of sequence part in range, opt in `/ (a..b)`.
+    fn get_slice_mut(&mut self, start: usize, end: usize) -> Vec<&mut Self>
+    {
+        match self {
+            Value::Table(t) => {
+                let end = end.min(t.seq.len());
+                match t.seq.get_mut(start..end) {
+                    Some(items) => items.iter_mut().collect(),
+                    None => Vec::new()
+                }
+            }
+            _ => Vec::new()
+        }
+    }
+
+    /// Get keys of map part.
+    fn get_keys(&self) -> Vec<&str>
+    {
+        match self {
+            Value::Table(t) => t.map.keys().map(|k| k.as_str()).collect(),
+            _ => Vec::new()
+        }
+    }
 }
 
 impl ValueReader for Value {
@@ -210,4 +243,19 @@ fn main()
     println!("v/key | '': {s}");
 
     println!("v: {:?}", v);
+
+    let seq = v.path() / (0..2) | 0;
+    println!("v/(0..2) | 0: {:?}", seq);
+    for node in v.path_mut() / (1..) {
+        let _ = node << 654;
+    }
+
+    let all = v.query("$.*").unwrap();
+    println!("v.query($.*): {:?}", all);
+
+    if let Some(cur) = v.cursor().item(0) {
+        let next = cur.next_sibling().map(|c| c.ptr() | 0);
+        println!("v/0 next sibling | 0: {:?}", next);
+        println!("v/0 parent depth: {:?}", cur.parent().map(|c| c.depth()));
+    }
 }
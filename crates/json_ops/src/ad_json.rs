@@ -27,6 +27,45 @@ impl ValuePath for Value {
     {
         self.get_mut(k)
     }
+
+    /// Get length of array.
+    fn get_len(&self) -> usize
+    {
+        self.as_array().map_or(0, |a| a.len())
+    }
+
+    /// Get items of array in range.
+    fn get_slice(&self, start: usize, end: usize) -> Vec<&Self>
+    {
+        match self.as_array() {
+            Some(a) => a.get(start..end.min(a.len())).unwrap_or(&[]).iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get mutable items of array in range.
+    fn get_slice_mut(&mut self, start: usize, end: usize) -> Vec<&mut Self>
+    {
+        match self.as_array_mut() {
+            Some(a) => {
+                let end = end.min(a.len());
+                match a.get_mut(start..end) {
+                    Some(items) => items.iter_mut().collect(),
+                    None => Vec::new(),
+                }
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Get keys of map.
+    fn get_keys(&self) -> Vec<&str>
+    {
+        match self.as_object() {
+            Some(m) => m.keys().map(|k| k.as_str()).collect(),
+            None => Vec::new(),
+        }
+    }
 }
 
 impl ValueReader for Value {
@@ -82,6 +121,38 @@ impl ValueReader for Value {
             _ => rhs
         }
     }
+
+    /// Scalar view for json null, bool, number and string.
+    fn get_scalar(&self) -> Option<Scalar<'_>> {
+        match self {
+            Value::Null => Some(Scalar::Null),
+            Value::Bool(tf) => Some(Scalar::Bool(*tf)),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => Some(Scalar::Int(i)),
+                None => n.as_f64().map(Scalar::Float),
+            },
+            Value::String(s) => Some(Scalar::Str(s)),
+            _ => None
+        }
+    }
+
+    /// Kind of json node.
+    fn get_kind(&self) -> NodeKind {
+        match self {
+            Value::Null => NodeKind::Null,
+            Value::Bool(_) => NodeKind::Bool,
+            Value::Number(_) => NodeKind::Number,
+            Value::String(_) => NodeKind::String,
+            Value::Array(_) => NodeKind::Array,
+            Value::Object(_) => NodeKind::Object,
+        }
+    }
+
+    /// Parse json object or array text in string node.
+    fn get_embedded(&self) -> Option<Self> {
+        let tree: Value = serde_json::from_str(self.as_str()?).ok()?;
+        if tree.is_object() || tree.is_array() { Some(tree) } else { None }
+    }
 }
 
 impl ValueWriter for Value {
@@ -113,4 +184,71 @@ impl ValueWriter for Value {
         self
     }
 
+    /// Insert item into array.
+    fn insert_array<T>(&mut self, i: usize, val: T) -> bool where Value: From<T> {
+        match self.as_array_mut() {
+            Some(v) if i <= v.len() => { v.insert(i, Value::from(val)); true },
+            _ => false,
+        }
+    }
+
+    /// Replace items of array in range.
+    fn splice_array(&mut self, start: usize, end: usize, items: Vec<Self>) -> Option<Vec<Self>> {
+        let array = self.as_array_mut()?;
+        let end = end.min(array.len());
+        Some(array.splice(start.min(end)..end, items).collect())
+    }
+
+    /// Pop the last item of array.
+    fn pop_array(&mut self) -> Option<Self> {
+        self.as_array_mut()?.pop()
+    }
+
+    /// Remove member from object.
+    fn remove_key(&mut self, key: &str) -> Option<Self> {
+        self.as_object_mut()?.remove(key)
+    }
+
+    /// Remove item from array, shift the later items.
+    fn remove_index(&mut self, i: usize) -> Option<Self> {
+        let array = self.as_array_mut()?;
+        if i < array.len() { Some(array.remove(i)) } else { None }
+    }
+
+    /// Insert `null` for missing key, and turn `null` into object.
+    fn entry_key(&mut self, key: &str) -> Option<&mut Self> {
+        if self.is_null() {
+            *self = json!({});
+        }
+        Some(self.as_object_mut()?.entry(key).or_insert(Value::Null))
+    }
+
+    /// Pad `null` beyond the end, and turn `null` into array.
+    fn entry_index(&mut self, i: usize) -> Option<&mut Self> {
+        let len = self.as_array().map_or(0, Vec::len);
+        if i.checked_sub(len).is_some_and(|pad| pad >= ENTRY_PAD_LIMIT) {
+            return None;
+        }
+        if self.is_null() {
+            *self = json!([]);
+        }
+        let array = self.as_array_mut()?;
+        if array.len() <= i {
+            array.resize(i.checked_add(1)?, Value::Null);
+        }
+        array.get_mut(i)
+    }
+
+    /// Serialize object or array node to compact json string.
+    fn collapse_embedded(&mut self) {
+        if self.is_object() || self.is_array() {
+            *self = Value::String(self.to_string());
+        }
+    }
+
+    /// Just clone the json value.
+    fn from_json(json: &Value) -> Option<Self> {
+        Some(json.clone())
+    }
+
 }
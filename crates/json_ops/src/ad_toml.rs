@@ -26,6 +26,45 @@ impl ValuePath for Value {
     {
         self.get_mut(k)
     }
+
+    /// Get length of array.
+    fn get_len(&self) -> usize
+    {
+        self.as_array().map_or(0, |a| a.len())
+    }
+
+    /// Get items of array in range.
+    fn get_slice(&self, start: usize, end: usize) -> Vec<&Self>
+    {
+        match self.as_array() {
+            Some(a) => a.get(start..end.min(a.len())).unwrap_or(&[]).iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get mutable items of array in range.
+    fn get_slice_mut(&mut self, start: usize, end: usize) -> Vec<&mut Self>
+    {
+        match self.as_array_mut() {
+            Some(a) => {
+                let end = end.min(a.len());
+                match a.get_mut(start..end) {
+                    Some(items) => items.iter_mut().collect(),
+                    None => Vec::new(),
+                }
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Get keys of map.
+    fn get_keys(&self) -> Vec<&str>
+    {
+        match self.as_table() {
+            Some(m) => m.keys().map(|k| k.as_str()).collect(),
+            None => Vec::new(),
+        }
+    }
 }
 
 impl ValueReader for Value {
@@ -79,6 +118,30 @@ impl ValueReader for Value {
             _ => rhs
         }
     }
+
+    /// Scalar view for toml string, integer, float and boolean.
+    /// Note that toml has no null, and datetime is not supported.
+    fn get_scalar(&self) -> Option<Scalar<'_>> {
+        match self {
+            Value::String(s) => Some(Scalar::Str(s)),
+            Value::Integer(i) => Some(Scalar::Int(*i)),
+            Value::Float(f) => Some(Scalar::Float(*f)),
+            Value::Boolean(tf) => Some(Scalar::Bool(*tf)),
+            _ => None
+        }
+    }
+
+    /// Kind of toml node, datetime is other kind.
+    fn get_kind(&self) -> NodeKind {
+        match self {
+            Value::String(_) => NodeKind::String,
+            Value::Integer(_) | Value::Float(_) => NodeKind::Number,
+            Value::Boolean(_) => NodeKind::Bool,
+            Value::Array(_) => NodeKind::Array,
+            Value::Table(_) => NodeKind::Object,
+            Value::Datetime(_) => NodeKind::Other,
+        }
+    }
 }
 
 impl ValueWriter for Value {
@@ -110,4 +173,63 @@ impl ValueWriter for Value {
         self
     }
 
+    /// Insert item into array.
+    fn insert_array<T>(&mut self, i: usize, val: T) -> bool where Value: From<T> {
+        match self.as_array_mut() {
+            Some(v) if i <= v.len() => { v.insert(i, Value::from(val)); true },
+            _ => false,
+        }
+    }
+
+    /// Replace items of array in range.
+    fn splice_array(&mut self, start: usize, end: usize, items: Vec<Self>) -> Option<Vec<Self>> {
+        let array = self.as_array_mut()?;
+        let end = end.min(array.len());
+        Some(array.splice(start.min(end)..end, items).collect())
+    }
+
+    /// Pop the last item of array.
+    fn pop_array(&mut self) -> Option<Self> {
+        self.as_array_mut()?.pop()
+    }
+
+    /// Remove member from table.
+    fn remove_key(&mut self, key: &str) -> Option<Self> {
+        self.as_table_mut()?.remove(key)
+    }
+
+    /// Remove item from array, shift the later items.
+    fn remove_index(&mut self, i: usize) -> Option<Self> {
+        let array = self.as_array_mut()?;
+        if i < array.len() { Some(array.remove(i)) } else { None }
+    }
+
+    /// Insert empty table for missing key, as toml has no null.
+    fn entry_key(&mut self, key: &str) -> Option<&mut Self> {
+        let table = self.as_table_mut()?;
+        Some(table.entry(key).or_insert_with(|| Value::Table(toml::Table::new())))
+    }
+
+    /// Pad empty table beyond the end, and turn empty table into array,
+    /// which the pointer only asks for the table it just created.
+    fn entry_index(&mut self, i: usize) -> Option<&mut Self> {
+        let len = self.as_array().map_or(0, Vec::len);
+        if i.checked_sub(len).is_some_and(|pad| pad >= ENTRY_PAD_LIMIT) {
+            return None;
+        }
+        if self.as_table().is_some_and(|t| t.is_empty()) {
+            *self = Value::Array(toml::value::Array::new());
+        }
+        let array = self.as_array_mut()?;
+        if array.len() <= i {
+            array.resize(i.checked_add(1)?, Value::Table(toml::Table::new()));
+        }
+        array.get_mut(i)
+    }
+
+    /// Convert by serde, fail for json null that toml has no.
+    fn from_json(json: &serde_json::Value) -> Option<Self> {
+        Value::try_from(json).ok()
+    }
+
 }
@@ -1,7 +1,11 @@
 #![allow(unused_variables)] //< many defalut implement for trait
 
+use std::fmt;
 use crate::valueptr::ValuePtr;
 use crate::valueptr::ValuePtrMut;
+use crate::cursor::ValueCursor;
+use crate::jsonpath::{JsonPath, JsonPathError};
+use crate::syntax::{PathSyntax, PathSyntaxError};
 
 /// Yield json (or more generic value) pointer to support operator `/` overload.
 /// All methods have defualt implementation, only override as needed.
@@ -32,6 +36,47 @@ pub trait ValuePath {
         None
     }
 
+    /// Get the number of items if it is array-like node, otherwise 0.
+    /// Together with `get_keys` it is used to iterate over child nodes.
+    fn get_len(&self) -> usize
+    {
+        0
+    }
+
+    /// Get all the keys if it is map-like node, otherwise empty.
+    fn get_keys(&self) -> Vec<&str>
+    {
+        Vec::new()
+    }
+
+    /// Get all child nodes with how each is reached, array items first then
+    /// object members. Used to walk the subtree such as `descendants_where()`.
+    /// Default implementation collect by `get_len` and `get_keys`.
+    fn get_children(&self) -> Vec<(ChildKey<'_>, &Self)>
+    {
+        let mut out: Vec<_> = (0..self.get_len())
+            .filter_map(|i| self.get_index(i).map(|v| (ChildKey::Index(i), v)))
+            .collect();
+        out.extend(self.get_keys().into_iter().filter_map(|k| self.get_key(k).map(|v| (ChildKey::Key(k), v))));
+        out
+    }
+
+    /// Get items of array in range `start..end`, for operator `/ (a..b)`.
+    /// The `end` may exceed the length of array, only existed items returned.
+    /// Default implementation collect items by `get_index` one by one.
+    fn get_slice(&self, start: usize, end: usize) -> Vec<&Self>
+    {
+        (start..end).map_while(|i| self.get_index(i)).collect()
+    }
+
+    /// Get mutable items of array in range `start..end`, for operator `/ (a..b)`.
+    /// Default implementation return empty, as cannot borrow many items
+    /// by `get_index_mut`, override it to support mutable slice.
+    fn get_slice_mut(&mut self, start: usize, end: usize) -> Vec<&mut Self>
+    {
+        Vec::new()
+    }
+
     /// Construct immutable value pointer to some initial node.
     /// Used to begin operator `/` chain.
     fn path<'tr>(&'tr self) -> ValuePtr<'tr, Self>
@@ -48,6 +93,22 @@ pub trait ValuePath {
         self.path().pathto(p)
     }
 
+    /// Select all nodes that match a JSONPath (RFC 9535) query, such as
+    /// `$.service[*].name`, which may be none or many.
+    /// Would fail only when the query string has invalid syntax.
+    fn query<'tr>(&'tr self, q: &str) -> Result<Vec<&'tr Self>, JsonPathError>
+        where Self: ValueReader + Sized
+    {
+        Ok(JsonPath::parse(q)?.select(self))
+    }
+
+    /// Construct navigable cursor to this node as root.
+    fn cursor(&self) -> ValueCursor<'_, Self>
+        where Self: Sized
+    {
+        ValueCursor::new(self)
+    }
+
     /// Construct mutable value pointer to some initial node.
     /// Used to begin operator `/` chain.
     fn path_mut<'tr>(&'tr mut self) -> ValuePtrMut<'tr, Self>
@@ -56,6 +117,14 @@ pub trait ValuePath {
         ValuePtrMut::new(Some(self))
     }
 
+    /// Construct mutable value pointer in entry mode, that `/` creates
+    /// missing nodes along the way, see `ValuePtrMut::entry()`.
+    fn path_create<'tr>(&'tr mut self) -> ValuePtrMut<'tr, Self>
+        where Self: ValueReader + ValueWriter + Sized
+    {
+        self.path_mut().entry()
+    }
+
     /// Construct mutable value pointer and move it follwoing sub path.
     /// Can also begin operator `/` chain, or got target within one call.
     fn pathto_mut<'tr>(&'tr mut self, p: &str) -> ValuePtrMut<'tr, Self>
@@ -63,8 +132,63 @@ pub trait ValuePath {
     {
         self.path_mut().pathto(p)
     }
+
+    /// Construct value pointer and move it following sub path in the
+    /// specified syntax, see `ValuePtr::pathto_with()`.
+    fn pathto_with<'tr>(&'tr self, p: &str, syntax: PathSyntax) -> Result<ValuePtr<'tr, Self>, PathSyntaxError>
+        where Self: ValueReader + Sized
+    {
+        self.path().pathto_with(p, syntax)
+    }
+
+    /// Construct mutable value pointer and move it following sub path in the
+    /// specified syntax, see `ValuePtrMut::pathto_with()`.
+    fn pathto_mut_with<'tr>(&'tr mut self, p: &str, syntax: PathSyntax) -> Result<ValuePtrMut<'tr, Self>, PathSyntaxError>
+        where Self: ValueReader + ValueWriter + Sized
+    {
+        self.path_mut().pathto_with(p, syntax)
+    }
+}
+
+
+/// How a child node is reached from its parent, by index or key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChildKey<'tr> {
+    Index(usize),
+    Key(&'tr str),
+}
+
+impl fmt::Display for ChildKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChildKey::Index(i) => write!(f, "{}", i),
+            ChildKey::Key(k) => f.write_str(k),
+        }
+    }
+}
+
+/// All child nodes, array items first then object members.
+pub(crate) fn children<Value>(node: &Value) -> Vec<&Value>
+where Value: ValuePath
+{
+    node.get_children().into_iter().map(|(_, v)| v).collect()
 }
 
+/// Most blank items to pad beyond the end of array in entry mode,
+/// farther index fails instead of allocating a huge array.
+pub(crate) const ENTRY_PAD_LIMIT: usize = 1024;
+
+/// Array-like node is array of any length, or non-empty node without key
+/// if the tree does not tell its kind. Empty object is not array-like.
+pub(crate) fn is_array_like<Value>(node: &Value) -> bool
+where Value: ValuePath + ValueReader
+{
+    match node.get_kind() {
+        NodeKind::Array => true,
+        NodeKind::Other => node.get_len() > 0 && node.get_keys().is_empty(),
+        _ => false,
+    }
+}
 
 /// The rust type for scalar json node, which can used after operator `|` to read,
 /// or/and operator `<<` to write. Only support `i64` for integer, to make use literal
@@ -77,6 +201,71 @@ impl ScalarValue for f64 {}
 impl ScalarValue for bool {}
 impl ScalarValue for () {}
 
+/// The rust type for array index after operator `/`, negative index of signed
+/// type count from end of array, as `-1` refer to the last item.
+/// Note that integer literal default to `i32`.
+pub trait PathIndex: Copy + fmt::Display {
+    /// Convert to the real index of array with `len` items.
+    fn index_in(self, len: usize) -> Option<usize>;
+}
+
+impl PathIndex for usize {
+    fn index_in(self, _len: usize) -> Option<usize> { Some(self) }
+}
+
+impl PathIndex for isize {
+    fn index_in(self, len: usize) -> Option<usize> {
+        if self >= 0 {
+            Some(self as usize)
+        }
+        else {
+            len.checked_sub(self.unsigned_abs())
+        }
+    }
+}
+
+impl PathIndex for i32 {
+    fn index_in(self, len: usize) -> Option<usize> { (self as isize).index_in(len) }
+}
+
+/// Borrowed view of a scalar node, to compare node value in generic way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scalar<'tr> {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(&'tr str),
+}
+
+/// The kind of node, to report what is found where path failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+    /// Unknown or other kind of node, such as toml datetime.
+    Other,
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NodeKind::Null => "null",
+            NodeKind::Bool => "bool",
+            NodeKind::Number => "number",
+            NodeKind::String => "string",
+            NodeKind::Array => "array",
+            NodeKind::Object => "object",
+            NodeKind::Other => "other",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Extend method to read Value, and support operator `| rhs_default`.
 /// The default implementation just return `rhs` without any treatment.
 /// It is dependent for concrete `Value` type how extract value from node.
@@ -100,6 +289,31 @@ pub trait ValueReader {
     /// Support operator `| false`. 
     /// For json pointer, it will also try to parse from string node.
     fn get_bool(&self, rhs: bool) -> bool { rhs }
+
+    /// Get the scalar view of leaf node, or `None` for array or object node.
+    /// Used to compare node value such as in filter expression of query.
+    fn get_scalar(&self) -> Option<Scalar<'_>> { None }
+
+    /// Get the kind of node, used to explain why path failed.
+    /// The default implementation only tell scalar kinds from `get_scalar`.
+    fn get_kind(&self) -> NodeKind
+    {
+        match self.get_scalar() {
+            Some(Scalar::Null) => NodeKind::Null,
+            Some(Scalar::Bool(_)) => NodeKind::Bool,
+            Some(Scalar::Int(_)) | Some(Scalar::Float(_)) => NodeKind::Number,
+            Some(Scalar::Str(_)) => NodeKind::String,
+            None => NodeKind::Other,
+        }
+    }
+
+    /// Parse the text in string node as a tree, to navigate into embedded
+    /// json by `EmbedCache`. Return `None` if not string or not valid text.
+    fn get_embedded(&self) -> Option<Self>
+        where Self: Sized
+    {
+        None
+    }
 }
 
 /// Extend method to read Value, and support operator `<< rhs`.
@@ -130,5 +344,76 @@ pub trait ValueWriter {
     {
         self
     }
+
+    /// Insert a item to array node at index, shift the later items.
+    /// Return `false` if not array, index beyond the end, or not supported.
+    fn insert_array<T>(&mut self, i: usize, val: T) -> bool
+        where Self: From<T>
+    {
+        false
+    }
+
+    /// Replace items of array node in range `start..end` by `items`, the
+    /// range is clamped to the array. Return the removed items, or `None`
+    /// if not array or not supported by default.
+    fn splice_array(&mut self, start: usize, end: usize, items: Vec<Self>) -> Option<Vec<Self>>
+        where Self: Sized
+    {
+        None
+    }
+
+    /// Remove the last item of array node and return it.
+    fn pop_array(&mut self) -> Option<Self>
+        where Self: Sized
+    {
+        None
+    }
+
+    /// Remove member by key from object node, return the detached value.
+    /// Return `None` if not found, or not supported by default.
+    fn remove_key(&mut self, key: &str) -> Option<Self>
+        where Self: Sized
+    {
+        None
+    }
+
+    /// Remove item by index from array node, return the detached value.
+    /// Return `None` if out of range, or not supported by default.
+    fn remove_index(&mut self, i: usize) -> Option<Self>
+        where Self: Sized
+    {
+        None
+    }
+
+    /// Get child by key for auto-vivifying pointer, insert a blank node if
+    /// missing, and turn blank node into object first.
+    /// Return `None` for other kind of node, or not supported by default.
+    fn entry_key(&mut self, key: &str) -> Option<&mut Self>
+    {
+        None
+    }
+
+    /// Get item by index for auto-vivifying pointer, turn blank node into
+    /// array first, and pad blank items if beyond the end.
+    /// Return `None` for other kind of node, index too far beyond the end,
+    /// or not supported by default.
+    fn entry_index(&mut self, i: usize) -> Option<&mut Self>
+    {
+        None
+    }
+
+    /// Serialize the tree from `get_embedded()` back to string node in
+    /// place. Used by `EmbeddedMut`.
+    fn collapse_embedded(&mut self)
+    {
+    }
+
+    /// Convert json value to this type, used to apply json patch document.
+    /// Return `None` if it cannot be held, or not supported by default.
+    fn from_json(json: &serde_json::Value) -> Option<Self>
+        where Self: Sized
+    {
+        None
+    }
 }
 
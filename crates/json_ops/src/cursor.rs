@@ -0,0 +1,168 @@
+//! Navigable cursor that keeps its ancestor stack, so can move up to parent
+//! or side to sibling, besides down to child as `ValuePtr`.
+
+use std::ops::Deref;
+use crate::adopter::*;
+use crate::pointer::*;
+
+/// How a child is reached from its parent node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step<'tr> {
+    Key(&'tr str),
+    Index(usize),
+}
+
+/// Cursor to a node in value tree, with the ancestor nodes from root.
+/// It is built only on `ValuePath` trait, so works for json, toml or custom
+/// tree. All move methods return a new cursor, or `None` if no such node.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// let v = json!({"service": [{"name": "serv_1"}, {"name": "serv_2", "port": 80}]});
+/// let name = v.cursor().child("service").unwrap().item(1).unwrap().child("name").unwrap();
+/// assert_eq!(name.key(), Some("name"));
+/// let serv = name.parent().unwrap();
+/// assert_eq!(serv.index(), Some(1));
+/// assert_eq!(serv.prev_sibling().unwrap().child("name").unwrap().ptr() | "", "serv_1");
+/// assert_eq!(name.root().depth(), 0);
+/// ```
+pub struct ValueCursor<'tr, Value>
+where Value: ValuePath
+{
+    node: &'tr Value,
+    /// Ancestor nodes from root, with the step to the next node.
+    stack: Vec<(&'tr Value, Step<'tr>)>,
+}
+
+impl<'tr, Value> Clone for ValueCursor<'tr, Value>
+where Value: ValuePath
+{
+    fn clone(&self) -> Self {
+        Self { node: self.node, stack: self.stack.clone() }
+    }
+}
+
+impl<'tr, Value> ValueCursor<'tr, Value>
+where Value: ValuePath
+{
+    /// Create cursor to the root node.
+    pub fn new(root: &'tr Value) -> Self {
+        Self { node: root, stack: Vec::new() }
+    }
+
+    /// The current node.
+    pub fn value(&self) -> &'tr Value {
+        self.node
+    }
+
+    /// Number of ancestors, zero for root.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Move down to child by key.
+    pub fn child(&self, key: &str) -> Option<Self> {
+        let keys = self.node.get_keys();
+        let key = keys.into_iter().find(|k| *k == key)?;
+        self.down(Step::Key(key))
+    }
+
+    /// Move down to item of array by index.
+    pub fn item(&self, i: usize) -> Option<Self> {
+        self.down(Step::Index(i))
+    }
+
+    /// Move up to parent node.
+    pub fn parent(&self) -> Option<Self> {
+        let mut stack = self.stack.clone();
+        let (node, _) = stack.pop()?;
+        Some(Self { node, stack })
+    }
+
+    /// Move to the root node where the cursor begins.
+    pub fn root(&self) -> Self {
+        match self.stack.first() {
+            Some((root, _)) => Self::new(root),
+            None => Self::new(self.node),
+        }
+    }
+
+    /// Move to the next sibling, the next item of array or the next key
+    /// in the order of `get_keys()`.
+    pub fn next_sibling(&self) -> Option<Self> {
+        self.sibling(1)
+    }
+
+    /// Move to the previous sibling.
+    pub fn prev_sibling(&self) -> Option<Self> {
+        self.sibling(-1)
+    }
+
+    /// The key in parent object, `None` for root or array item.
+    pub fn key(&self) -> Option<&'tr str> {
+        match self.stack.last() {
+            Some((_, Step::Key(key))) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// The index in parent array, `None` for root or object child.
+    pub fn index(&self) -> Option<usize> {
+        match self.stack.last() {
+            Some((_, Step::Index(i))) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// The path from root as json pointer string, such as `/service/1/name`.
+    pub fn location(&self) -> String {
+        let tokens: Vec<String> = self.stack.iter()
+            .map(|(_, step)| match step {
+                Step::Key(key) => key.to_string(),
+                Step::Index(i) => i.to_string(),
+            })
+            .collect();
+        join_pointer(&tokens)
+    }
+
+    /// Convert to pointer of the current node, to use operator `/` or `|`.
+    pub fn ptr(&self) -> crate::ValuePtr<'tr, Value>
+    where Value: ValueReader
+    {
+        crate::ValuePtr::new(Some(self.node))
+    }
+
+    fn down(&self, step: Step<'tr>) -> Option<Self> {
+        let node = match step {
+            Step::Key(key) => self.node.get_key(key)?,
+            Step::Index(i) => self.node.get_index(i)?,
+        };
+        let mut stack = self.stack.clone();
+        stack.push((self.node, step));
+        Some(Self { node, stack })
+    }
+
+    fn sibling(&self, offset: isize) -> Option<Self> {
+        let (parent, step) = *self.stack.last()?;
+        let next = match step {
+            Step::Index(i) => Step::Index(i.checked_add_signed(offset)?),
+            Step::Key(key) => {
+                let keys = parent.get_keys();
+                let pos = keys.iter().position(|k| *k == key)?;
+                Step::Key(keys.get(pos.checked_add_signed(offset)?)?)
+            }
+        };
+        self.parent()?.down(next)
+    }
+}
+
+/// Deref the cursor to the current node.
+impl<'tr, Value> Deref for ValueCursor<'tr, Value>
+where Value: ValuePath
+{
+    type Target = Value;
+    fn deref(&self) -> &Self::Target {
+        self.node
+    }
+}
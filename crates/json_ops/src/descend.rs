@@ -0,0 +1,52 @@
+//! Walk the whole subtree depth-first, to search descendants by key name
+//! or by predicate on path and node.
+
+use crate::adopter::*;
+use crate::pointer::escape_token;
+
+/// Iterator over the matched descendants of a node, in depth-first order
+/// with array items before object members, yielding the json pointer path
+/// and the node. Created by `ValuePtr::descendants_named()` or
+/// `ValuePtr::descendants_where()`.
+pub struct Descendants<'tr, Value, F> {
+    /// Nodes to visit, with their path and how reached from the parent.
+    stack: Vec<(String, ChildKey<'tr>, &'tr Value)>,
+    pred: F,
+}
+
+impl<'tr, Value, F> Descendants<'tr, Value, F>
+where Value: ValuePath, F: FnMut(ChildKey<'_>, &str, &Value) -> bool
+{
+    /// Begin from `node` whose path is `base`, the node itself is excluded.
+    pub(crate) fn new(node: Option<&'tr Value>, base: String, pred: F) -> Self {
+        let mut walk = Self { stack: Vec::new(), pred };
+        if let Some(node) = node {
+            walk.push_children(&base, node);
+        }
+        walk
+    }
+
+    fn push_children(&mut self, base: &str, node: &'tr Value) {
+        let children = node.get_children();
+        for (step, child) in children.into_iter().rev() {
+            let path = format!("{}/{}", base, escape_token(&step.to_string()));
+            self.stack.push((path, step, child));
+        }
+    }
+}
+
+impl<'tr, Value, F> Iterator for Descendants<'tr, Value, F>
+where Value: ValuePath, F: FnMut(ChildKey<'_>, &str, &Value) -> bool
+{
+    type Item = (String, &'tr Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((path, step, node)) = self.stack.pop() {
+            self.push_children(&path, node);
+            if (self.pred)(step, &path, node) {
+                return Some((path, node));
+            }
+        }
+        None
+    }
+}
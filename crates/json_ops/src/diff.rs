@@ -0,0 +1,292 @@
+//! Compare two trees and generate json patch (RFC 6902) that turns the old
+//! one into the new one, for json or other backend as toml.
+
+use crate::adopter::*;
+use crate::patch::{Patch, PatchOp};
+use crate::pointer::join_pointer;
+
+/// How to compare array nodes in `diff_with()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayDiff {
+    /// Compare items at the same index, then add or remove the tail.
+    #[default]
+    Index,
+    /// Keep the longest common subsequence of items, and move the same item
+    /// to new position instead of remove and add it again.
+    Lcs,
+}
+
+/// Generate patch from `old` to `new`, compare array by index.
+/// The same as `diff_with(old, new, ArrayDiff::Index)`.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{diff, apply_patch};
+/// let old = json!({"host": {"port": 80}, "tags": ["a", "b"], "debug": true});
+/// let new = json!({"host": {"port": 8080}, "tags": ["a"], "a/b": 1});
+/// let patch = diff(&old, &new);
+/// assert_eq!(patch.to_json(), json!([
+///     {"op": "remove", "path": "/debug"},
+///     {"op": "replace", "path": "/host/port", "value": 8080},
+///     {"op": "remove", "path": "/tags/1"},
+///     {"op": "add", "path": "/a~1b", "value": 1},
+/// ]));
+/// let mut v = old.clone();
+/// assert!(apply_patch(&mut v, &patch).is_ok());
+/// assert_eq!(v, new);
+/// ```
+pub fn diff<Value>(old: &Value, new: &Value) -> Patch<Value>
+where Value: ValuePath + ValueReader + Clone + PartialEq
+{
+    diff_with(old, new, ArrayDiff::Index)
+}
+
+/// Generate patch from `old` to `new`, compare array as `arrays` says.
+/// Object members are removed first, then changed, then added, in the order
+/// of `get_keys()`. The changed node of different kind is replaced.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{diff_with, ArrayDiff};
+/// let old = json!(["a", "b", "c", "d"]);
+/// let new = json!(["d", "a", "b", "c"]);
+/// assert_eq!(diff_with(&old, &new, ArrayDiff::Index).0.len(), 4);
+/// let patch = diff_with(&old, &new, ArrayDiff::Lcs);
+/// assert_eq!(patch.to_json(), json!([{"op": "move", "from": "/3", "path": "/0"}]));
+/// ```
+pub fn diff_with<Value>(old: &Value, new: &Value, arrays: ArrayDiff) -> Patch<Value>
+where Value: ValuePath + ValueReader + Clone + PartialEq
+{
+    let mut differ = Differ { arrays, trail: Vec::new(), ops: Vec::new() };
+    differ.node(old, new);
+    Patch(differ.ops)
+}
+
+/// State to walk two trees in parallel.
+struct Differ<Value> {
+    arrays: ArrayDiff,
+    /// Path tokens from root to the current node.
+    trail: Vec<String>,
+    ops: Vec<PatchOp<Value>>,
+}
+
+/// Step to turn old array into new one, by item index of them.
+enum Edit {
+    Keep,
+    /// Item in old array which is moved somewhere.
+    Leave(usize),
+    /// Item in new array which is moved from the old item.
+    Arrive(usize),
+    /// Item of old array is changed to item of new array.
+    Change(usize, usize),
+    Remove,
+    Insert(usize),
+}
+
+/// Where an item of array is now while editing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Old(usize),
+    New,
+}
+
+impl<Value> Differ<Value>
+where Value: ValuePath + ValueReader + Clone + PartialEq
+{
+    /// Json pointer of child under current node.
+    fn path(&self, token: &str) -> String {
+        let mut path = join_pointer(&self.trail);
+        path.push_str(&join_pointer(&[token]));
+        path
+    }
+
+    /// Compare node at the current path.
+    fn node(&mut self, old: &Value, new: &Value) {
+        if old == new {
+            return;
+        }
+        match (old.get_kind(), new.get_kind()) {
+            (NodeKind::Object, NodeKind::Object) => self.object(old, new),
+            (NodeKind::Array, NodeKind::Array) => match self.arrays {
+                ArrayDiff::Index => self.array_index(old, new),
+                ArrayDiff::Lcs => self.array_lcs(old, new),
+            },
+            _ => {
+                let path = join_pointer(&self.trail);
+                self.ops.push(PatchOp::Replace { path, value: new.clone() });
+            }
+        }
+    }
+
+    /// Compare child node under the token.
+    fn child(&mut self, token: String, old: &Value, new: &Value) {
+        self.trail.push(token);
+        self.node(old, new);
+        self.trail.pop();
+    }
+
+    fn object(&mut self, old: &Value, new: &Value) {
+        let old_keys = old.get_keys();
+        let new_keys = new.get_keys();
+        for key in old_keys.iter().filter(|k| new.get_key(k).is_none()) {
+            self.ops.push(PatchOp::Remove { path: self.path(key) });
+        }
+        for key in old_keys.iter() {
+            if let (Some(a), Some(b)) = (old.get_key(key), new.get_key(key)) {
+                self.child(key.to_string(), a, b);
+            }
+        }
+        for key in new_keys.into_iter().filter(|k| old.get_key(k).is_none()) {
+            if let Some(b) = new.get_key(key) {
+                self.ops.push(PatchOp::Add { path: self.path(key), value: b.clone() });
+            }
+        }
+    }
+
+    fn array_index(&mut self, old: &Value, new: &Value) {
+        let (old_len, new_len) = (old.get_len(), new.get_len());
+        for i in 0..old_len.min(new_len) {
+            if let (Some(a), Some(b)) = (old.get_index(i), new.get_index(i)) {
+                self.child(i.to_string(), a, b);
+            }
+        }
+        for i in (new_len..old_len).rev() {
+            self.ops.push(PatchOp::Remove { path: self.path(&i.to_string()) });
+        }
+        for i in old_len..new_len {
+            if let Some(b) = new.get_index(i) {
+                self.ops.push(PatchOp::Add { path: self.path(&i.to_string()), value: b.clone() });
+            }
+        }
+    }
+
+    /// Edit array following the steps, track the items in `slots` to know
+    /// the index of each op in the array changed by previous ops.
+    fn array_lcs(&mut self, old: &Value, new: &Value) {
+        let olds = old.get_slice(0, old.get_len());
+        let news = new.get_slice(0, new.get_len());
+        let mut slots: Vec<Slot> = (0..olds.len()).map(Slot::Old).collect();
+        let mut cur = 0;
+        for edit in lcs_edits(&olds, &news) {
+            match edit {
+                Edit::Keep => cur += 1,
+                Edit::Leave(i) => {
+                    // stay until moved, unless moved already
+                    if slots.get(cur) == Some(&Slot::Old(i)) {
+                        cur += 1;
+                    }
+                }
+                Edit::Arrive(i) => {
+                    let Some(pos) = slots.iter().position(|s| *s == Slot::Old(i)) else { continue };
+                    slots.remove(pos);
+                    if pos < cur {
+                        cur -= 1;
+                    }
+                    if pos != cur {
+                        let from = self.path(&pos.to_string());
+                        self.ops.push(PatchOp::Move { from, path: self.path(&cur.to_string()) });
+                    }
+                    slots.insert(cur, Slot::New);
+                    cur += 1;
+                }
+                Edit::Change(i, j) => {
+                    self.child(cur.to_string(), olds[i], news[j]);
+                    slots[cur] = Slot::New;
+                    cur += 1;
+                }
+                Edit::Remove => {
+                    self.ops.push(PatchOp::Remove { path: self.path(&cur.to_string()) });
+                    slots.remove(cur);
+                }
+                Edit::Insert(j) => {
+                    self.ops.push(PatchOp::Add { path: self.path(&cur.to_string()), value: news[j].clone() });
+                    slots.insert(cur, Slot::New);
+                    cur += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Steps to turn `olds` into `news`, keep the longest common subsequence,
+/// pair the same item removed and inserted as move, and pair the rest
+/// removed and inserted items in the same gap as change.
+fn lcs_edits<Value>(olds: &[&Value], news: &[&Value]) -> Vec<Edit>
+where Value: PartialEq
+{
+    let (n, m) = (olds.len(), news.len());
+    // table[i][j] is the lcs length of olds[i..] and news[j..]
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if olds[i] == news[j] {
+                table[i + 1][j + 1] + 1
+            }
+            else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    // gaps of removed and inserted items between kept ones
+    let mut gaps: Vec<(Vec<usize>, Vec<usize>)> = vec![(Vec::new(), Vec::new())];
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && olds[i] == news[j] {
+            gaps.push((Vec::new(), Vec::new()));
+            i += 1;
+            j += 1;
+        }
+        else if j == m || (i < n && table[i + 1][j] >= table[i][j + 1]) {
+            gaps.last_mut().unwrap().0.push(i);
+            i += 1;
+        }
+        else {
+            gaps.last_mut().unwrap().1.push(j);
+            j += 1;
+        }
+    }
+
+    // the old item each new item moved from
+    let mut moved: Vec<Option<usize>> = vec![None; m];
+    let mut leaving: Vec<bool> = vec![false; n];
+    for (_, ins) in gaps.iter() {
+        for &j in ins {
+            let from = gaps.iter().flat_map(|(dels, _)| dels)
+                .find(|&&i| !leaving[i] && olds[i] == news[j]);
+            if let Some(&i) = from {
+                leaving[i] = true;
+                moved[j] = Some(i);
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    for (k, (dels, ins)) in gaps.iter().enumerate() {
+        if k > 0 {
+            edits.push(Edit::Keep);
+        }
+        let (mut a, mut b) = (0, 0);
+        loop {
+            if let Some(&i) = dels.get(a).filter(|&&i| leaving[i]) {
+                edits.push(Edit::Leave(i));
+                a += 1;
+            }
+            else if let Some(i) = ins.get(b).and_then(|&j| moved[j]) {
+                edits.push(Edit::Arrive(i));
+                b += 1;
+            }
+            else {
+                match (dels.get(a), ins.get(b)) {
+                    (Some(&i), Some(&j)) => edits.push(Edit::Change(i, j)),
+                    (Some(_), None) => edits.push(Edit::Remove),
+                    (None, Some(&j)) => edits.push(Edit::Insert(j)),
+                    (None, None) => break,
+                }
+                a += usize::from(a < dels.len());
+                b += usize::from(b < ins.len());
+            }
+        }
+    }
+    edits
+}
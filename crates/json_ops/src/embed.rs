@@ -0,0 +1,194 @@
+//! Opt-in traversal into json text embedded in string nodes, such as
+//! `{"payload": "{\"a\":1}"}`. Immutable pointer parses the string into a
+//! cache, while mutable pointer expands it in place and serializes it back.
+
+use std::cell::{OnceCell, RefCell};
+use crate::adopter::*;
+use crate::pointer::Token;
+use crate::valueptr::ValuePtrMut;
+
+/// String nodes expanded in place, to serialize back later.
+pub(crate) struct EmbedLog<Value> {
+    expanded: RefCell<Vec<Expanded<Value>>>,
+}
+
+/// The path from root to the expanded node, and the original string node.
+struct Expanded<Value> {
+    path: Vec<Token>,
+    text: Value,
+}
+
+/// Cache of trees parsed from string nodes, each node is parsed only once.
+/// Pass it to `ValuePtr::with_embedded()` to navigate into string nodes.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, EmbedCache};
+/// let v = json!({"payload": "{\"a\": {\"b\": [1, 2]}}"});
+/// assert_eq!((v.path() / "payload" / "a").is_none(), true);
+/// let cache = EmbedCache::new();
+/// let root = v.path().with_embedded(&cache);
+/// assert_eq!(root / "payload" / "a" / "b" / 1 | 0, 2);
+/// assert_eq!(root.pathto("payload/a/b/0") | 0, 1);
+/// ```
+pub struct EmbedCache<Value> {
+    head: OnceCell<Box<Entry<Value>>>,
+}
+
+/// Parsed tree of a string node, identified by address and text.
+struct Entry<Value> {
+    addr: usize,
+    text: String,
+    tree: Value,
+    next: OnceCell<Box<Entry<Value>>>,
+}
+
+impl<Value> Default for EmbedCache<Value> {
+    fn default() -> Self {
+        Self { head: OnceCell::new() }
+    }
+}
+
+impl<Value> EmbedCache<Value>
+where Value: ValueReader
+{
+    /// Create empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the tree parsed from string node, parse and keep it if not yet.
+    pub(crate) fn open(&self, node: &Value) -> Option<&Value> {
+        let addr = node as *const Value as usize;
+        let text = node.get_str("");
+        let mut cell = &self.head;
+        while let Some(entry) = cell.get() {
+            if entry.addr == addr && entry.text == text {
+                return Some(&entry.tree);
+            }
+            cell = &entry.next;
+        }
+        let tree = node.get_embedded()?;
+        let entry = cell.get_or_init(|| {
+            Box::new(Entry { addr, text: text.to_string(), tree, next: OnceCell::new() })
+        });
+        Some(&entry.tree)
+    }
+}
+
+/// Mutable root that expands json text in string nodes in place while
+/// navigating by `/` or `pathto()`, and serializes them back to compact
+/// string when dropped, so write through it updates the embedded json.
+/// The string node not modified is kept as the original text.
+///
+/// The expanded nodes are tracked by path. Edit by `insert_at()`, `<<` and
+/// other methods of the pointer serializes back the expanded nodes under
+/// the changed node first, but direct edit through `DerefMut` that shifts
+/// array items is not tracked, as well as the pointer set by range.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::EmbeddedMut;
+/// let mut v = json!({"payload": "{\"a\": 1}"});
+/// let _ = EmbeddedMut::new(&mut v).path() / "payload" / "a" << 2;
+/// assert_eq!(v, json!({"payload": "{\"a\":2}"}));
+/// ```
+pub struct EmbeddedMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    root: &'tr mut Value,
+    expanded: EmbedLog<Value>,
+}
+
+impl<'tr, Value> EmbeddedMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    /// Wrap the root node to begin.
+    pub fn new(root: &'tr mut Value) -> Self {
+        Self { root, expanded: EmbedLog { expanded: RefCell::new(Vec::new()) } }
+    }
+
+    /// Construct mutable pointer to the root, to begin `/` chain.
+    pub fn path(&mut self) -> ValuePtrMut<'_, Value> {
+        ValuePtrMut::new(Some(&mut *self.root)).with_embed_log(&self.expanded)
+    }
+
+    /// Construct mutable pointer and move it following sub path.
+    pub fn pathto(&mut self, p: &str) -> ValuePtrMut<'_, Value> {
+        self.path().pathto(p)
+    }
+}
+
+impl<'tr, Value> Drop for EmbeddedMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    /// Serialize the expanded nodes back, the inner one first.
+    fn drop(&mut self) {
+        self.expanded.settle(self.root, &[], true);
+    }
+}
+
+impl<Value> EmbedLog<Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    /// Replace string node in place with the tree parsed from its text, and
+    /// log its path reached by `tokens` after `trail`.
+    pub(crate) fn expand(&self, node: &mut Value, trail: &[Token], tokens: &[Token]) {
+        if node.get_kind() != NodeKind::String {
+            return;
+        }
+        if let Some(tree) = node.get_embedded() {
+            let text = std::mem::replace(node, tree);
+            let path = trail.iter().chain(tokens).cloned().collect();
+            self.expanded.borrow_mut().push(Expanded { path, text });
+        }
+    }
+
+    /// Serialize back the expanded nodes under `node` at path `base`, the
+    /// inner one first, and forget them. Include `node` itself if `inclusive`.
+    pub(crate) fn settle(&self, node: &mut Value, base: &[Token], inclusive: bool) {
+        let mut expanded = self.expanded.borrow_mut();
+        for i in (0..expanded.len()).rev() {
+            let path = &expanded[i].path;
+            if !path.starts_with(base) || (path.len() == base.len() && !inclusive) {
+                continue;
+            }
+            let entry = expanded.remove(i);
+            if let Some(target) = descend_mut(node, &entry.path[base.len()..]) {
+                collapse(target, entry.text);
+            }
+        }
+    }
+
+    /// Forget the expanded node at `path`, which is overwritten.
+    pub(crate) fn forget(&self, path: &[Token]) {
+        self.expanded.borrow_mut().retain(|entry| entry.path != path);
+    }
+}
+
+/// Get the node by resolved path tokens.
+fn descend_mut<'a, Value>(node: &'a mut Value, path: &[Token]) -> Option<&'a mut Value>
+where Value: ValuePath
+{
+    path.iter().try_fold(node, |node, token| match token {
+        Token::Key(key) => node.get_key_mut(key),
+        Token::Index(i) => node.get_index_mut(*i),
+    })
+}
+
+/// Serialize the expanded node back, but restore the original string node
+/// if the tree is not modified, to keep its format.
+fn collapse<Value>(node: &mut Value, text: Value)
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    node.collapse_embedded();
+    if node.get_kind() != NodeKind::String {
+        return;
+    }
+    if let Some(mut tree) = text.get_embedded() {
+        tree.collapse_embedded();
+        if tree.get_str("") == node.get_str("") {
+            *node = text;
+        }
+    }
+}
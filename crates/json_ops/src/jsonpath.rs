@@ -0,0 +1,741 @@
+//! JSONPath query (RFC 9535) to select many nodes from a value tree.
+//! It is built only on `ValuePath` and `ValueReader` trait, so can apply to
+//! json, toml or any custom tree that implement these traits.
+//!
+//! Supported syntax include:
+//!
+//! * root `$` and current node `@` (in filter) identifier.
+//! * child segment `.name`, `.*`, `['name', 0, -1, 1:5:2, *, ?filter]`.
+//! * descendant segment `..name`, `..*`, `..[selectors]`.
+//! * filter with comparison `== != < <= > >=`, logical `&& || !`,
+//!   existence test and parentheses, for example `?@.port > 8000`.
+//!
+//! Function extensions such as `length()` or `match()` are not supported.
+
+use std::fmt;
+use std::cmp::Ordering;
+use crate::adopter::*;
+
+/// Error when parse JSONPath query string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPathError {
+    /// Byte offset in the query string where the error found.
+    pub pos: usize,
+    /// What is wrong there.
+    pub msg: String,
+}
+
+impl fmt::Display for JsonPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid jsonpath at {}: {}", self.pos, self.msg)
+    }
+}
+
+impl std::error::Error for JsonPathError {}
+
+/// Compiled JSONPath query, can be parsed once and used to select many times.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, JsonPath};
+/// let v = json!({"service": [
+///     {"name": "a", "port": 8080},
+///     {"name": "b", "port": 80}
+/// ]});
+/// let q = JsonPath::parse("$.service[?@.port > 8000].name").unwrap();
+/// assert_eq!(q.select(&v), vec![&json!("a")]);
+/// assert_eq!(v.query("$..name").unwrap(), vec![&json!("a"), &json!("b")]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Or(Vec<Filter>),
+    And(Vec<Filter>),
+    Not(Box<Filter>),
+    Exist(SubQuery),
+    Compare(Operand, CmpOp, Operand),
+}
+
+/// Embedded query in filter, begin from `@` if relative, or `$` otherwise.
+#[derive(Debug, Clone, PartialEq)]
+struct SubQuery {
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Literal(Literal),
+    Query(SubQuery),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl JsonPath {
+    /// Parse query string that must begin with `$`.
+    pub fn parse(q: &str) -> Result<Self, JsonPathError> {
+        let mut parser = Parser { src: q, pos: 0 };
+        if !parser.eat('$') {
+            return parser.error("query must begin with `$`");
+        }
+        let segments = parser.parse_segments()?;
+        if parser.pos < q.len() {
+            return parser.error("unexpected character");
+        }
+        Ok(Self { segments })
+    }
+
+    /// Select all matched nodes from `root`, in the order of RFC 9535.
+    pub fn select<'tr, Value>(&self, root: &'tr Value) -> Vec<&'tr Value>
+    where Value: ValuePath + ValueReader
+    {
+        select_segments(&self.segments, root, root)
+    }
+}
+
+/* ------------------------------------------------------------ */
+
+const MAX_INT: i64 = (1 << 53) - 1;
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, msg: &str) -> Result<T, JsonPathError> {
+        Err(JsonPathError { pos: self.pos, msg: msg.to_string() })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn peek_str(&self, s: &str) -> bool {
+        self.src[self.pos..].starts_with(s)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        if self.peek_str(s) {
+            self.pos += s.len();
+            true
+        }
+        else {
+            false
+        }
+    }
+
+    fn skip_blank(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// Parse as many segments as possible, blank is allowed between them.
+    fn parse_segments(&mut self) -> Result<Vec<Segment>, JsonPathError> {
+        let mut segments = Vec::new();
+        loop {
+            let save = self.pos;
+            self.skip_blank();
+            if self.eat_str("..") {
+                let selectors = match self.peek() {
+                    Some('[') => self.parse_bracket()?,
+                    Some('*') => { self.bump(); vec![Selector::Wildcard] }
+                    _ => vec![Selector::Name(self.parse_member_name()?)],
+                };
+                segments.push(Segment::Descendant(selectors));
+            }
+            else if self.eat('.') {
+                let selectors = match self.peek() {
+                    Some('*') => { self.bump(); vec![Selector::Wildcard] }
+                    _ => vec![Selector::Name(self.parse_member_name()?)],
+                };
+                segments.push(Segment::Child(selectors));
+            }
+            else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.parse_bracket()?));
+            }
+            else {
+                self.pos = save;
+                break;
+            }
+        }
+        Ok(segments)
+    }
+
+    /// Parse member name shorthand after `.` or `..`.
+    fn parse_member_name(&mut self) -> Result<String, JsonPathError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' || !c.is_ascii() => {}
+            _ => return self.error("expect member name, `*` or `[`"),
+        }
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii() {
+                self.bump();
+            }
+            else {
+                break;
+            }
+        }
+        Ok(self.src[start..self.pos].to_string())
+    }
+
+    /// Parse `[selector, ...]`.
+    fn parse_bracket(&mut self) -> Result<Vec<Selector>, JsonPathError> {
+        self.bump(); // [
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_blank();
+            selectors.push(self.parse_selector()?);
+            self.skip_blank();
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            if !self.eat(',') {
+                return self.error("expect `,` or `]`");
+            }
+        }
+    }
+
+    fn parse_selector(&mut self) -> Result<Selector, JsonPathError> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.parse_string()?)),
+            Some('*') => { self.bump(); Ok(Selector::Wildcard) }
+            Some('?') => {
+                self.bump();
+                self.skip_blank();
+                Ok(Selector::Filter(self.parse_or()?))
+            }
+            Some(':' | '-' | '0'..='9') => self.parse_index_or_slice(),
+            _ => self.error("invalid selector"),
+        }
+    }
+
+    fn parse_index_or_slice(&mut self) -> Result<Selector, JsonPathError> {
+        let start = self.parse_opt_int()?;
+        self.skip_blank();
+        if !self.eat(':') {
+            return match start {
+                Some(i) => Ok(Selector::Index(i)),
+                None => self.error("expect index"),
+            };
+        }
+        self.skip_blank();
+        let end = self.parse_opt_int()?;
+        self.skip_blank();
+        let mut step = None;
+        if self.eat(':') {
+            self.skip_blank();
+            step = self.parse_opt_int()?;
+        }
+        Ok(Selector::Slice(start, end, step))
+    }
+
+    fn parse_opt_int(&mut self) -> Result<Option<i64>, JsonPathError> {
+        match self.peek() {
+            Some('-' | '0'..='9') => self.parse_int().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Parse integer without leading zero, and `-0` is not allowed.
+    fn parse_int(&mut self) -> Result<i64, JsonPathError> {
+        let start = self.pos;
+        let neg = self.eat('-');
+        let digits = self.pos;
+        while let Some('0'..='9') = self.peek() {
+            self.bump();
+        }
+        let text = &self.src[digits..self.pos];
+        if text.is_empty() || (text.starts_with('0') && (text.len() > 1 || neg)) {
+            self.pos = start;
+            return self.error("invalid integer");
+        }
+        match self.src[start..self.pos].parse::<i64>() {
+            Ok(i) if (-MAX_INT..=MAX_INT).contains(&i) => Ok(i),
+            _ => {
+                self.pos = start;
+                self.error("integer out of range")
+            }
+        }
+    }
+
+    /// Parse single or double quoted string literal.
+    fn parse_string(&mut self) -> Result<String, JsonPathError> {
+        let quote = self.bump().unwrap_or('"');
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None => return self.error("unterminated string"),
+                Some(c) if c == quote => return Ok(out),
+                Some('\\') => out.push(self.parse_escape(quote)?),
+                Some(c) if c < ' ' => return self.error("control character in string"),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self, quote: char) -> Result<char, JsonPathError> {
+        let c = match self.bump() {
+            Some('b') => '\u{08}',
+            Some('f') => '\u{0C}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('/') => '/',
+            Some('\\') => '\\',
+            Some(c) if c == quote => c,
+            Some('u') => {
+                let hi = self.parse_hex4()?;
+                if (0xD800..0xDC00).contains(&hi) {
+                    if !self.eat_str("\\u") {
+                        return self.error("expect low surrogate");
+                    }
+                    let lo = self.parse_hex4()?;
+                    if !(0xDC00..0xE000).contains(&lo) {
+                        return self.error("invalid low surrogate");
+                    }
+                    let code = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+                    return char::from_u32(code).map_or_else(|| self.error("invalid unicode"), Ok);
+                }
+                return char::from_u32(hi).map_or_else(|| self.error("invalid unicode"), Ok);
+            }
+            _ => return self.error("invalid escape"),
+        };
+        Ok(c)
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonPathError> {
+        let hex = self.src.get(self.pos..self.pos + 4).unwrap_or("");
+        match u32::from_str_radix(hex, 16) {
+            Ok(code) if hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                self.pos += 4;
+                Ok(code)
+            }
+            _ => self.error("expect 4 hex digits"),
+        }
+    }
+
+    /* -------------------- filter expression -------------------- */
+
+    fn parse_or(&mut self) -> Result<Filter, JsonPathError> {
+        let mut items = vec![self.parse_and()?];
+        loop {
+            self.skip_blank();
+            if !self.eat_str("||") {
+                break;
+            }
+            self.skip_blank();
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { Filter::Or(items) })
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, JsonPathError> {
+        let mut items = vec![self.parse_basic()?];
+        loop {
+            self.skip_blank();
+            if !self.eat_str("&&") {
+                break;
+            }
+            self.skip_blank();
+            items.push(self.parse_basic()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { Filter::And(items) })
+    }
+
+    fn parse_basic(&mut self) -> Result<Filter, JsonPathError> {
+        if self.peek() == Some('!') && !self.peek_str("!=") {
+            self.bump();
+            self.skip_blank();
+            return Ok(Filter::Not(Box::new(self.parse_basic()?)));
+        }
+        if self.eat('(') {
+            self.skip_blank();
+            let inner = self.parse_or()?;
+            self.skip_blank();
+            if !self.eat(')') {
+                return self.error("expect `)`");
+            }
+            return Ok(inner);
+        }
+
+        let start = self.pos;
+        let lhs = self.parse_operand()?;
+        self.skip_blank();
+        let op = match self.parse_cmp_op() {
+            Some(op) => op,
+            None => {
+                return match lhs {
+                    Operand::Query(q) => Ok(Filter::Exist(q)),
+                    Operand::Literal(_) => self.error("expect comparison operator"),
+                };
+            }
+        };
+        if !is_singular(&lhs) {
+            self.pos = start;
+            return self.error("non-singular query in comparison");
+        }
+        self.skip_blank();
+        let start = self.pos;
+        let rhs = self.parse_operand()?;
+        if !is_singular(&rhs) {
+            self.pos = start;
+            return self.error("non-singular query in comparison");
+        }
+        Ok(Filter::Compare(lhs, op, rhs))
+    }
+
+    fn parse_cmp_op(&mut self) -> Option<CmpOp> {
+        for (s, op) in [("==", CmpOp::Eq), ("!=", CmpOp::Ne), ("<=", CmpOp::Le),
+            (">=", CmpOp::Ge), ("<", CmpOp::Lt), (">", CmpOp::Gt)] {
+            if self.eat_str(s) {
+                return Some(op);
+            }
+        }
+        None
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, JsonPathError> {
+        match self.peek() {
+            Some('@') | Some('$') => {
+                let relative = self.bump() == Some('@');
+                let segments = self.parse_segments()?;
+                Ok(Operand::Query(SubQuery { relative, segments }))
+            }
+            Some('\'' | '"') => Ok(Operand::Literal(Literal::Str(self.parse_string()?))),
+            Some('-' | '0'..='9') => self.parse_number(),
+            _ => {
+                for (s, lit) in [("true", Literal::Bool(true)), ("false", Literal::Bool(false)),
+                    ("null", Literal::Null)] {
+                    if self.eat_str(s) {
+                        return Ok(Operand::Literal(lit));
+                    }
+                }
+                if self.peek().is_some_and(|c| c.is_ascii_lowercase()) {
+                    return self.error("function extension is not supported");
+                }
+                self.error("expect query or literal")
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Operand, JsonPathError> {
+        let start = self.pos;
+        self.eat('-');
+        let digits = self.pos;
+        while let Some('0'..='9') = self.peek() {
+            self.bump();
+        }
+        let int = &self.src[digits..self.pos];
+        if int.is_empty() || (int.len() > 1 && int.starts_with('0')) {
+            self.pos = start;
+            return self.error("invalid number");
+        }
+        if self.eat('.') && !self.eat_digits() {
+            return self.error("expect fraction digits");
+        }
+        if self.eat('e') || self.eat('E') {
+            if !self.eat('-') {
+                self.eat('+');
+            }
+            if !self.eat_digits() {
+                return self.error("expect exponent digits");
+            }
+        }
+        match self.src[start..self.pos].parse::<f64>() {
+            Ok(f) => Ok(Operand::Literal(Literal::Number(f))),
+            Err(_) => {
+                self.pos = start;
+                self.error("invalid number")
+            }
+        }
+    }
+
+    fn eat_digits(&mut self) -> bool {
+        let start = self.pos;
+        while let Some('0'..='9') = self.peek() {
+            self.bump();
+        }
+        self.pos > start
+    }
+}
+
+/// Singular query only has name or index selector in child segments.
+fn is_singular(operand: &Operand) -> bool {
+    match operand {
+        Operand::Literal(_) => true,
+        Operand::Query(q) => q.segments.iter().all(|seg| match seg {
+            Segment::Child(sel) => sel.len() == 1
+                && matches!(sel[0], Selector::Name(_) | Selector::Index(_)),
+            Segment::Descendant(_) => false,
+        }),
+    }
+}
+
+/* ------------------------------------------------------------ */
+
+/// The node itself and all its descendants in pre-order.
+fn descendants<'tr, Value>(node: &'tr Value, out: &mut Vec<&'tr Value>)
+where Value: ValuePath
+{
+    out.push(node);
+    for child in children(node) {
+        descendants(child, out);
+    }
+}
+
+fn select_segments<'tr, Value>(segments: &[Segment], root: &'tr Value, start: &'tr Value)
+    -> Vec<&'tr Value>
+where Value: ValuePath + ValueReader
+{
+    let mut nodes = vec![start];
+    for segment in segments {
+        let mut next = Vec::new();
+        for node in nodes {
+            match segment {
+                Segment::Child(selectors) => {
+                    for selector in selectors {
+                        select_one(selector, root, node, &mut next);
+                    }
+                }
+                Segment::Descendant(selectors) => {
+                    let mut all = Vec::new();
+                    descendants(node, &mut all);
+                    for desc in all {
+                        for selector in selectors {
+                            select_one(selector, root, desc, &mut next);
+                        }
+                    }
+                }
+            }
+        }
+        nodes = next;
+    }
+    nodes
+}
+
+fn select_one<'tr, Value>(selector: &Selector, root: &'tr Value, node: &'tr Value,
+    out: &mut Vec<&'tr Value>)
+where Value: ValuePath + ValueReader
+{
+    match selector {
+        Selector::Name(name) => out.extend(node.get_key(name)),
+        Selector::Wildcard => out.extend(children(node)),
+        Selector::Index(i) => {
+            let len = node.get_len() as i64;
+            let i = if *i < 0 { len + i } else { *i };
+            if i >= 0 {
+                out.extend(node.get_index(i as usize));
+            }
+        }
+        Selector::Slice(start, end, step) => {
+            let len = node.get_len() as i64;
+            for i in slice_indices(len, *start, *end, *step) {
+                out.extend(node.get_index(i as usize));
+            }
+        }
+        Selector::Filter(filter) => {
+            for child in children(node) {
+                if eval_filter(filter, root, child) {
+                    out.push(child);
+                }
+            }
+        }
+    }
+}
+
+/// Array indices selected by slice, following RFC 9535 section 2.3.4.2.
+fn slice_indices(len: i64, start: Option<i64>, end: Option<i64>, step: Option<i64>) -> Vec<i64> {
+    let step = step.unwrap_or(1);
+    let normalize = |i: i64| if i >= 0 { i } else { len + i };
+    let mut out = Vec::new();
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            out.push(i);
+            i += step;
+        }
+    }
+    else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = end.map_or(-1, |e| normalize(e).clamp(-1, len - 1));
+        let mut i = upper;
+        while lower < i {
+            out.push(i);
+            i += step;
+        }
+    }
+    out
+}
+
+/* ------------------------------------------------------------ */
+
+fn eval_filter<'tr, Value>(filter: &Filter, root: &'tr Value, current: &'tr Value) -> bool
+where Value: ValuePath + ValueReader
+{
+    match filter {
+        Filter::Or(items) => items.iter().any(|f| eval_filter(f, root, current)),
+        Filter::And(items) => items.iter().all(|f| eval_filter(f, root, current)),
+        Filter::Not(inner) => !eval_filter(inner, root, current),
+        Filter::Exist(q) => !eval_query(q, root, current).is_empty(),
+        Filter::Compare(lhs, op, rhs) => {
+            let lhs = eval_operand(lhs, root, current);
+            let rhs = eval_operand(rhs, root, current);
+            match op {
+                CmpOp::Eq => compare_eq(&lhs, &rhs),
+                CmpOp::Ne => !compare_eq(&lhs, &rhs),
+                CmpOp::Lt => compare_lt(&lhs, &rhs),
+                CmpOp::Le => compare_lt(&lhs, &rhs) || compare_eq(&lhs, &rhs),
+                CmpOp::Gt => compare_lt(&rhs, &lhs),
+                CmpOp::Ge => compare_lt(&rhs, &lhs) || compare_eq(&lhs, &rhs),
+            }
+        }
+    }
+}
+
+fn eval_query<'tr, Value>(q: &SubQuery, root: &'tr Value, current: &'tr Value) -> Vec<&'tr Value>
+where Value: ValuePath + ValueReader
+{
+    let start = if q.relative { current } else { root };
+    select_segments(&q.segments, root, start)
+}
+
+/// The value of operand to compare, a node may be non-scalar.
+enum Comparable<'a, Value> {
+    Nothing,
+    Node(&'a Value),
+    Scalar(Scalar<'a>),
+}
+
+fn eval_operand<'a, Value>(operand: &'a Operand, root: &'a Value, current: &'a Value)
+    -> Comparable<'a, Value>
+where Value: ValuePath + ValueReader
+{
+    match operand {
+        Operand::Literal(lit) => Comparable::Scalar(match lit {
+            Literal::Null => Scalar::Null,
+            Literal::Bool(tf) => Scalar::Bool(*tf),
+            Literal::Number(f) => Scalar::Float(*f),
+            Literal::Str(s) => Scalar::Str(s),
+        }),
+        Operand::Query(q) => match eval_query(q, root, current).first() {
+            Some(node) => match node.get_scalar() {
+                Some(scalar) => Comparable::Scalar(scalar),
+                None => Comparable::Node(*node),
+            },
+            None => Comparable::Nothing,
+        },
+    }
+}
+
+fn compare_eq<Value>(lhs: &Comparable<'_, Value>, rhs: &Comparable<'_, Value>) -> bool
+where Value: ValuePath + ValueReader
+{
+    match (lhs, rhs) {
+        (Comparable::Nothing, Comparable::Nothing) => true,
+        (Comparable::Scalar(a), Comparable::Scalar(b)) => scalar_cmp(a, b) == Some(Ordering::Equal),
+        (Comparable::Node(a), Comparable::Node(b)) => deep_eq(*a, *b),
+        _ => false,
+    }
+}
+
+fn compare_lt<Value>(lhs: &Comparable<'_, Value>, rhs: &Comparable<'_, Value>) -> bool {
+    match (lhs, rhs) {
+        (Comparable::Scalar(a @ (Scalar::Int(_) | Scalar::Float(_) | Scalar::Str(_))), Comparable::Scalar(b)) =>
+            scalar_cmp(a, b) == Some(Ordering::Less),
+        _ => false,
+    }
+}
+
+/// Compare two scalars of the same type, numbers compare by value,
+/// while `None` means they are not comparable.
+fn scalar_cmp(lhs: &Scalar<'_>, rhs: &Scalar<'_>) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (Scalar::Null, Scalar::Null) => Some(Ordering::Equal),
+        (Scalar::Bool(a), Scalar::Bool(b)) if a == b => Some(Ordering::Equal),
+        (Scalar::Int(a), Scalar::Int(b)) => Some(a.cmp(b)),
+        (Scalar::Int(a), Scalar::Float(b)) => (*a as f64).partial_cmp(b),
+        (Scalar::Float(a), Scalar::Int(b)) => a.partial_cmp(&(*b as f64)),
+        (Scalar::Float(a), Scalar::Float(b)) => a.partial_cmp(b),
+        (Scalar::Str(a), Scalar::Str(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// Structural equality of two nodes, empty array and object differ.
+pub(crate) fn deep_eq<Value>(lhs: &Value, rhs: &Value) -> bool
+where Value: ValuePath + ValueReader
+{
+    match (lhs.get_scalar(), rhs.get_scalar()) {
+        (Some(a), Some(b)) => scalar_cmp(&a, &b) == Some(Ordering::Equal),
+        (None, None) if lhs.get_kind() == rhs.get_kind() => {
+            let len = lhs.get_len();
+            let keys = lhs.get_keys();
+            len == rhs.get_len() && keys.len() == rhs.get_keys().len()
+                && (0..len).all(|i| match (lhs.get_index(i), rhs.get_index(i)) {
+                    (Some(a), Some(b)) => deep_eq(a, b),
+                    _ => false,
+                })
+                && keys.into_iter().all(|k| match (lhs.get_key(k), rhs.get_key(k)) {
+                    (Some(a), Some(b)) => deep_eq(a, b),
+                    _ => false,
+                })
+        }
+        _ => false,
+    }
+}
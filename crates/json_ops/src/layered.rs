@@ -0,0 +1,191 @@
+//! Layered view over several trees, such as defaults, system, user and env
+//! config, where the later layer overrides the earlier one. Object nodes
+//! are merged only when navigate into them, nothing is copied.
+
+use std::ops::{Div, BitOr, Deref};
+use crate::adopter::*;
+use crate::valueptr::ValuePtr;
+use crate::pointer::split_loose;
+
+/// Pointer to the same path in a list of layered trees, support operator `/`
+/// and `|` like `ValuePtr`. A key is looked up from the highest layer down,
+/// object nodes are deep merged, while scalar or array node in higher layer
+/// shadows the whole node in lower layers.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::LayeredPtr;
+/// let defaults = json!({"host": {"ip": "0.0.0.0", "port": 80}, "debug": false});
+/// let user = json!({"host": {"port": 8080}});
+/// let env = json!({"debug": true});
+/// let conf = LayeredPtr::new(vec![&defaults, &user, &env]);
+/// assert_eq!(conf.clone() / "host" / "port" | 0, 8080);
+/// assert_eq!(conf.clone() / "host" / "ip" | "", "0.0.0.0");
+/// assert_eq!((conf.clone() / "host" / "ip").origin(), Some(0));
+/// assert_eq!((conf.clone() / "debug").origin(), Some(2));
+/// assert_eq!(conf / "host/port" | 0, 8080);
+/// ```
+pub struct LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    /// The nodes in effect with their layer index, the highest layer first,
+    /// and only the first may be not object.
+    layers: Vec<(usize, ValuePtr<'tr, Value>)>,
+    /// Pointer in the highest layer that has the node, or where it failed.
+    top: ValuePtr<'tr, Value>,
+}
+
+impl<'tr, Value> Clone for LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    fn clone(&self) -> Self {
+        Self { layers: self.layers.clone(), top: self.top }
+    }
+}
+
+impl<'tr, Value> LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    /// Create pointer to the roots of layers, where the later overrides the
+    /// earlier, so put defaults first and env overrides last.
+    pub fn new(roots: Vec<&'tr Value>) -> Self {
+        let layers = roots.into_iter().enumerate().rev()
+            .map(|(i, root)| (i, ValuePtr::new(Some(root))));
+        Self::merge(layers, ValuePtr::new(None))
+    }
+
+    /// The index of layer in the list passed to `new()` where the value
+    /// comes from, `None` if no layer has it.
+    pub fn origin(&self) -> Option<usize> {
+        self.layers.first().map(|(i, _)| *i)
+    }
+
+    /// The pointer in the layer where the value comes from, or the failed
+    /// pointer in the highest layer to report `error()`.
+    pub fn ptr(&self) -> ValuePtr<'tr, Value> {
+        self.top
+    }
+
+    /// All keys of the merged object node, in the order of lower layer first.
+    pub fn keys(&self) -> Vec<&'tr str> {
+        let mut keys: Vec<&'tr str> = Vec::new();
+        for (_, ptr) in self.layers.iter().rev() {
+            for key in ptr.map(|v| v.get_keys()).unwrap_or_default() {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys
+    }
+
+    /// Keep nodes from the highest layer until the first one not object.
+    fn merge<I>(ptrs: I, miss: ValuePtr<'tr, Value>) -> Self
+    where I: Iterator<Item = (usize, ValuePtr<'tr, Value>)>
+    {
+        let mut layers = Vec::new();
+        let mut top = None;
+        for (i, ptr) in ptrs {
+            match *ptr {
+                Some(v) => {
+                    layers.push((i, ptr));
+                    if v.get_kind() != NodeKind::Object {
+                        break;
+                    }
+                }
+                None => {
+                    top.get_or_insert(ptr);
+                }
+            }
+        }
+        let top = layers.first().map(|(_, ptr)| *ptr).or(top).unwrap_or(miss);
+        Self { layers, top }
+    }
+
+    /// Move each layer by the same step.
+    fn step_by<F>(&self, f: F) -> Self
+    where F: Fn(ValuePtr<'tr, Value>) -> ValuePtr<'tr, Value>
+    {
+        Self::merge(self.layers.iter().map(|(i, ptr)| (*i, f(*ptr))), f(self.top))
+    }
+
+    /// Resolve by single key, or joined path in the syntax of `pathto()` step
+    /// by step, so that the node of higher layer in the middle of path also
+    /// shadows lower layers.
+    fn path_str(&self, p: &str) -> Self {
+        let has_key = self.layers.iter().any(|(_, ptr)| ptr.and_then(|v| v.get_key(p)).is_some());
+        if has_key || split_loose(p).nth(1).is_none() {
+            return self.step_by(|ptr| ptr / p);
+        }
+        split_loose(p).fold(self.clone(), |layered, token| layered.step_by(|ptr| ptr / token.as_str()))
+    }
+}
+
+/// Overload `*` deref operator to the pointer where the value comes from.
+impl<'tr, Value> Deref for LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Target = ValuePtr<'tr, Value>;
+    fn deref(&self) -> &Self::Target {
+        &self.top
+    }
+}
+
+/// Path operator `/` by array index, in the highest layer that has it.
+impl<'tr, Value, I> Div<I> for LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader, I: PathIndex
+{
+    type Output = Self;
+    fn div(self, rhs: I) -> Self::Output {
+        self.step_by(|ptr| ptr / rhs)
+    }
+}
+
+/// Path operator `/` by key or joined path, merging object nodes.
+impl<'tr, Value> Div<&str> for LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, rhs: &str) -> Self::Output {
+        self.path_str(rhs)
+    }
+}
+
+impl<'tr, Value> Div<String> for LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, rhs: String) -> Self::Output {
+        self.path_str(rhs.as_str())
+    }
+}
+
+/// Pipe operator `|` to read the value from the highest layer, or default.
+impl<'tr, Value> BitOr<&'tr str> for LayeredPtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = &'tr str;
+    fn bitor(self, rhs: &'tr str) -> Self::Output {
+        self.top | rhs
+    }
+}
+
+/// Proxy of `|` operator overload for layered pointer.
+/// Would expand for String, i64, f64, bool.
+macro_rules! bitor_layered {
+    ($rhs:ty) => {
+        impl<'tr, Value> BitOr<$rhs> for LayeredPtr<'tr, Value>
+            where Value: ValuePath + ValueReader
+        {
+            type Output = $rhs;
+            fn bitor(self, rhs: $rhs) -> Self::Output {
+                self.top | rhs
+            }
+        }
+    };
+}
+
+bitor_layered!(String);
+bitor_layered!(i64);
+bitor_layered!(f64);
+bitor_layered!(bool);
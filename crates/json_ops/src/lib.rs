@@ -78,10 +78,41 @@
 //! ```
 //! When enable `toml` feature, then toml pointer can be used as the same as json.
 //!
+//! Besides the single node pointer, JSONPath (RFC 9535) query can select many
+//! nodes at once, with wildcard, recursive descent, array slice and filter.
+//!
+//! ```rust
+//! use serde_json::json;
+//! use json_ops::ValuePath;
+//!
+//! let v = json!({"service": [
+//!     {"name": "serv_1", "port": 8080},
+//!     {"name": "serv_2", "port": 80}
+//! ]});
+//! let names = v.query("$.service[*].name").unwrap();
+//! assert_eq!(names, vec!["serv_1", "serv_2"]);
+//! let names = v.query("$.service[?@.port > 8000].name").unwrap();
+//! assert_eq!(names, vec!["serv_1"]);
+//! ```
+//!
 
 mod valueptr;
 mod adopter;
 mod ad_json;
+mod jsonpath;
+mod pointer;
+mod patherr;
+mod syntax;
+mod cursor;
+mod relative;
+mod matcher;
+mod descend;
+mod layered;
+mod embed;
+mod trail;
+mod mount;
+mod patch;
+mod diff;
 
 #[cfg(feature = "toml")]
 mod ad_toml;
@@ -90,5 +121,45 @@ pub use adopter::ValuePath;
 pub use adopter::ValueReader;
 pub use adopter::ValueWriter;
 pub use adopter::ScalarValue;
+pub use adopter::Scalar;
+pub use adopter::PathIndex;
+pub use adopter::NodeKind;
+pub use adopter::ChildKey;
+pub use jsonpath::JsonPath;
+pub use jsonpath::JsonPathError;
+pub use patherr::PathError;
+pub use patherr::PathErrorKind;
+pub use patherr::EntryError;
+pub use syntax::PathSyntax;
+pub use syntax::PathSyntaxError;
+pub use pointer::Pointer;
+pub use pointer::Needle;
+pub use pointer::find_paths;
+#[doc(hidden)]
+pub use pointer::macros as __path;
 pub use valueptr::ValuePtr;
 pub use valueptr::ValuePtrMut;
+pub use valueptr::ValuePtrSet;
+pub use valueptr::ValuePtrSetMut;
+pub use valueptr::Wildcard;
+pub use cursor::ValueCursor;
+pub use relative::Relative;
+pub use relative::RelativeError;
+pub use matcher::KeyMatcher;
+pub use matcher::Glob;
+pub use descend::Descendants;
+pub use layered::LayeredPtr;
+pub use embed::EmbedCache;
+pub use embed::EmbeddedMut;
+pub use trail::PathTrail;
+pub use mount::MountedTree;
+pub use mount::MountPtr;
+pub use patch::apply_patch;
+pub use patch::Patch;
+pub use patch::PatchOp;
+pub use patch::PatchDoc;
+pub use patch::PatchError;
+pub use patch::PatchErrorKind;
+pub use diff::diff;
+pub use diff::diff_with;
+pub use diff::ArrayDiff;
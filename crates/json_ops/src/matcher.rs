@@ -0,0 +1,162 @@
+//! Policy to match object key in path, exactly or loosely.
+
+use crate::adopter::*;
+
+/// How the key in path matches the actual key of object node, set for
+/// pointer chain by `with_matcher()`. The exact key is always tried first,
+/// then search the first key matched in the order of `get_keys()`.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, KeyMatcher, PathTrail};
+/// let v = json!({"Server": {"max-conns": 100}});
+/// let trail = PathTrail::new();
+/// let node = v.path().with_trail(&trail).with_matcher(KeyMatcher::Normalized) / "server" / "maxConns";
+/// assert_eq!(node | 0, 100);
+/// assert_eq!(node.matched_key(), Some("max-conns"));
+/// assert_eq!(node.location(), Some("/Server/max-conns".to_string()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyMatcher {
+    /// Match the same key only.
+    #[default]
+    Exact,
+    /// Match key ignoring ASCII case, as `Port` and `port`.
+    IgnoreCase,
+    /// Match key ignoring ASCII case and `_` or `-` separator, so snake,
+    /// kebab and camel case are the same, as `max_conns`, `max-conns`
+    /// and `maxConns`.
+    Normalized,
+}
+
+impl KeyMatcher {
+    /// Check if the key in path matches the actual key.
+    pub fn matches(self, key: &str, actual: &str) -> bool {
+        match self {
+            KeyMatcher::Exact => key == actual,
+            KeyMatcher::IgnoreCase => key.eq_ignore_ascii_case(actual),
+            KeyMatcher::Normalized => normalize(key).eq(normalize(actual)),
+        }
+    }
+
+    /// Get child node by matched key.
+    pub(crate) fn get<'tr, Value>(self, node: &'tr Value, key: &str) -> Option<&'tr Value>
+    where Value: ValuePath
+    {
+        node.get_key(key).or_else(|| {
+            if self == KeyMatcher::Exact {
+                return None;
+            }
+            let actual = node.get_keys().into_iter().find(|k| self.matches(key, k))?;
+            node.get_key(actual)
+        })
+    }
+
+    /// Get the actual key matched in node.
+    pub(crate) fn actual_key<Value>(self, node: &Value, key: &str) -> Option<String>
+    where Value: ValuePath
+    {
+        if node.get_key(key).is_some() {
+            return Some(key.to_string());
+        }
+        if self == KeyMatcher::Exact {
+            return None;
+        }
+        node.get_keys().into_iter().find(|k| self.matches(key, k)).map(|k| k.to_string())
+    }
+}
+
+/// Glob pattern to match object keys, used in operator `/` to select all
+/// children whose key matches, and turn `ValuePtr` into `ValuePtrSet`.
+/// Support `*` for any chars, `?` for one char, `[abc]` or `[a-z]` for char
+/// class and `[!abc]` to negate it, and `\` to escape special char.
+/// Only for immutable pointer, as `Wildcard` is.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, Glob};
+/// let v = json!({"upstream_a": {"port": 80}, "upstream_b": {"port": 81}, "downstream": {"port": 90}});
+/// assert_eq!(v.path() / Glob("upstream_*") / "port" | 0, vec![80, 81]);
+/// assert_eq!(v.path() / Glob("*stream_[!a]") / "port" | 0, vec![81]);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Glob<'a>(pub &'a str);
+
+impl Glob<'_> {
+    /// Check if the key matches the glob pattern.
+    pub fn is_match(&self, key: &str) -> bool {
+        let pattern: Vec<char> = self.0.chars().collect();
+        let key: Vec<char> = key.chars().collect();
+        glob_match(&pattern, &key)
+    }
+}
+
+/// Match glob with backtrack to the last `*`.
+fn glob_match(pattern: &[char], key: &[char]) -> bool {
+    let (mut p, mut k) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while k < key.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, k));
+            p += 1;
+            continue;
+        }
+        if let Some(next) = match_one(pattern, p, key[k]) {
+            p = next;
+            k += 1;
+            continue;
+        }
+        match star {
+            Some((sp, sk)) => {
+                p = sp + 1;
+                k = sk + 1;
+                star = Some((sp, sk + 1));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Match one char at pattern position `p`, return the next position.
+fn match_one(pattern: &[char], p: usize, c: char) -> Option<usize> {
+    match *pattern.get(p)? {
+        '?' => Some(p + 1),
+        '\\' => match pattern.get(p + 1) {
+            Some(e) if *e == c => Some(p + 2),
+            _ => None,
+        },
+        '[' => {
+            let mut i = p + 1;
+            let negate = matches!(pattern.get(i), Some('!') | Some('^'));
+            if negate {
+                i += 1;
+            }
+            let mut hit = false;
+            let mut first = true;
+            while i < pattern.len() && (first || pattern[i] != ']') {
+                first = false;
+                if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' {
+                    hit |= pattern[i] <= c && c <= pattern[i + 2];
+                    i += 3;
+                }
+                else {
+                    hit |= pattern[i] == c;
+                    i += 1;
+                }
+            }
+            if i >= pattern.len() {
+                // no close bracket, as literal `[`
+                return if c == '[' { Some(p + 1) } else { None };
+            }
+            if hit != negate { Some(i + 1) } else { None }
+        }
+        x if x == c => Some(p + 1),
+        _ => None,
+    }
+}
+
+/// Lowercase chars of key without separator.
+fn normalize(key: &str) -> impl Iterator<Item = char> + '_ {
+    key.chars().filter(|c| *c != '_' && *c != '-').map(|c| c.to_ascii_lowercase())
+}
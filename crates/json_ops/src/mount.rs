@@ -0,0 +1,308 @@
+//! Read-only tree with other trees mounted under some paths of the base,
+//! which may be in different backends, such as toml under json, without
+//! copying any node.
+
+use std::ops::{Div, BitOr};
+use crate::adopter::*;
+use crate::pointer::split_loose;
+use crate::valueptr::hop_predicate;
+use crate::matcher::KeyMatcher;
+
+/// Object safe view of node in any backend, to mix trees.
+pub(crate) trait DynValue {
+    fn dyn_key(&self, k: &str) -> Option<&dyn DynValue>;
+    fn dyn_index(&self, i: usize) -> Option<&dyn DynValue>;
+    fn dyn_len(&self) -> usize;
+    fn dyn_keys(&self) -> Vec<&str>;
+    fn dyn_kind(&self) -> NodeKind;
+    fn dyn_scalar(&self) -> Option<Scalar<'_>>;
+    fn dyn_str<'a>(&'a self, rhs: &'a str) -> &'a str;
+    fn dyn_string(&self, rhs: String) -> String;
+    fn dyn_i64(&self, rhs: i64) -> i64;
+    fn dyn_f64(&self, rhs: f64) -> f64;
+    fn dyn_bool(&self, rhs: bool) -> bool;
+    fn dyn_predicate(&self, token: &str) -> Option<usize>;
+}
+
+impl<Value> DynValue for Value
+where Value: ValuePath + ValueReader
+{
+    fn dyn_key(&self, k: &str) -> Option<&dyn DynValue> {
+        self.get_key(k).map(|v| v as &dyn DynValue)
+    }
+
+    fn dyn_index(&self, i: usize) -> Option<&dyn DynValue> {
+        self.get_index(i).map(|v| v as &dyn DynValue)
+    }
+
+    fn dyn_len(&self) -> usize { self.get_len() }
+    fn dyn_keys(&self) -> Vec<&str> { self.get_keys() }
+    fn dyn_kind(&self) -> NodeKind { self.get_kind() }
+    fn dyn_scalar(&self) -> Option<Scalar<'_>> { self.get_scalar() }
+    fn dyn_str<'a>(&'a self, rhs: &'a str) -> &'a str { self.get_str(rhs) }
+    fn dyn_string(&self, rhs: String) -> String { self.get_string(rhs) }
+    fn dyn_i64(&self, rhs: i64) -> i64 { self.get_i64(rhs) }
+    fn dyn_f64(&self, rhs: f64) -> f64 { self.get_f64(rhs) }
+    fn dyn_bool(&self, rhs: bool) -> bool { self.get_bool(rhs) }
+
+    /// Index of item selected by predicate token as `[field=value]`.
+    fn dyn_predicate(&self, token: &str) -> Option<usize> {
+        hop_predicate(self, token, KeyMatcher::Exact).map(|(_, i)| i)
+    }
+}
+
+/// Base tree with other trees mounted under paths, to view them as one
+/// without deep clone. The mounted tree shadows the node of base at the
+/// same path, and the missing parents of mount point appear as objects.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::MountedTree;
+/// let base = json!({"name": "app", "plugins": {"bar": {"on": false}}});
+/// let foo = json!({"on": true, "port": 80});
+/// let tree = MountedTree::new(&base).mount("/plugins/foo", &foo);
+/// assert_eq!(tree.path() / "plugins" / "foo" / "port" | 0, 80);
+/// assert_eq!(tree.pathto("plugins/bar/on") | true, false);
+/// assert_eq!((tree.path() / "plugins").keys(), vec!["bar", "foo"]);
+/// ```
+pub struct MountedTree<'tr> {
+    base: &'tr dyn DynValue,
+    /// Mount points as path tokens, and the root of mounted tree.
+    mounts: Vec<(Vec<String>, &'tr dyn DynValue)>,
+}
+
+impl<'tr> MountedTree<'tr> {
+    /// Create with base tree, nothing mounted yet.
+    pub fn new<Value>(base: &'tr Value) -> Self
+    where Value: ValuePath + ValueReader
+    {
+        Self { base, mounts: Vec::new() }
+    }
+
+    /// Mount another tree at path such as `/plugins/foo`, in json pointer
+    /// syntax but the leading `/` is optional. Mount again at the same path
+    /// replaces the former one, and mount at root replaces the base.
+    pub fn mount<Value>(mut self, path: &str, tree: &'tr Value) -> Self
+    where Value: ValuePath + ValueReader
+    {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let tokens: Vec<String> = match path {
+            "" => Vec::new(),
+            _ => path.split('/').map(|x| x.replace("~1", "/").replace("~0", "~")).collect(),
+        };
+        self.mounts.retain(|(at, _)| *at != tokens);
+        self.mounts.push((tokens, tree));
+        self
+    }
+
+    /// Construct pointer to the root, to begin `/` chain.
+    pub fn path(&self) -> MountPtr<'_> {
+        let mounts: Vec<_> = self.mounts.iter().collect();
+        let node = match mounts.iter().find(|(at, _)| at.is_empty()) {
+            Some((_, tree)) => Node::Real(*tree),
+            None => Node::Real(self.base),
+        };
+        MountPtr { node: Some(node), depth: 0, mounts }
+    }
+
+    /// Construct pointer and move it following sub path.
+    pub fn pathto(&self, p: &str) -> MountPtr<'_> {
+        self.path() / p
+    }
+}
+
+/// Node in mounted tree, or a parent of mount point not in base.
+#[derive(Clone, Copy)]
+enum Node<'tr> {
+    Real(&'tr dyn DynValue),
+    Virtual,
+}
+
+/// Pointer to node in `MountedTree`, support operator `/` and `|` like
+/// `ValuePtr`.
+#[derive(Clone)]
+pub struct MountPtr<'tr> {
+    node: Option<Node<'tr>>,
+    /// Count of path tokens from root.
+    depth: usize,
+    /// Mount points under current path.
+    mounts: Vec<&'tr (Vec<String>, &'tr dyn DynValue)>,
+}
+
+impl<'tr> MountPtr<'tr> {
+    /// Whether it points to nothing.
+    pub fn is_none(&self) -> bool {
+        self.node.is_none()
+    }
+
+    /// The kind of node, parent of mount point is object, `None` if points
+    /// to nothing.
+    pub fn kind(&self) -> Option<NodeKind> {
+        match self.node? {
+            Node::Real(v) => Some(v.dyn_kind()),
+            Node::Virtual => Some(NodeKind::Object),
+        }
+    }
+
+    /// The scalar view of leaf node.
+    pub fn scalar(&self) -> Option<Scalar<'tr>> {
+        match self.node? {
+            Node::Real(v) => v.dyn_scalar(),
+            Node::Virtual => None,
+        }
+    }
+
+    /// Keys of object node, with mount points under it.
+    pub fn keys(&self) -> Vec<&'tr str> {
+        let mut keys = match self.node {
+            Some(Node::Real(v)) => v.dyn_keys(),
+            _ => Vec::new(),
+        };
+        for (at, _) in self.mounts.iter() {
+            if let Some(key) = at.get(self.depth) {
+                if !keys.contains(&key.as_str()) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys
+    }
+
+    /// Number of items if it is array node.
+    pub fn len(&self) -> usize {
+        match self.node {
+            Some(Node::Real(v)) => v.dyn_len(),
+            _ => 0,
+        }
+    }
+
+    /// Whether it has no array items.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Move down by one token, which is key, or index if `index` is some.
+    fn step(&self, token: &str, index: Option<usize>) -> Self {
+        let node = match self.node {
+            Some(node) => node,
+            None => return self.clone(),
+        };
+        let mounts: Vec<_> = self.mounts.iter()
+            .filter(|(at, _)| at.get(self.depth).map(|s| s.as_str()) == Some(token))
+            .copied()
+            .collect();
+        let depth = self.depth + 1;
+        let mounted = mounts.iter().find(|(at, _)| at.len() == depth).map(|(_, tree)| Node::Real(*tree));
+        let real = match (node, index) {
+            (Node::Real(v), Some(i)) => v.dyn_index(i),
+            (Node::Real(v), None) => v.dyn_key(token),
+            (Node::Virtual, _) => None,
+        };
+        let node = mounted.or(real.map(Node::Real))
+            .or(if mounts.is_empty() { None } else { Some(Node::Virtual) });
+        Self { node, depth, mounts }
+    }
+
+    /// Resolve by single key, or joined path in the syntax of `pathto()`.
+    fn path_str(&self, p: &str) -> Self {
+        let single = self.step(p, None);
+        if !single.is_none() {
+            return single;
+        }
+        split_loose(p).fold(self.clone(), |ptr, token| ptr.hop(&token))
+    }
+
+    /// Move down by one token of joined path, which is index of array,
+    /// key, or predicate as `key[field=value]` to select item of array.
+    fn hop(&self, token: &str) -> Self {
+        let index = match self.node {
+            Some(Node::Real(v)) if v.dyn_kind() == NodeKind::Array =>
+                token.parse::<isize>().ok().and_then(|i| i.index_in(v.dyn_len())),
+            _ => None,
+        };
+        if let Some(i) = index {
+            return self.step(&i.to_string(), Some(i));
+        }
+        let next = self.step(token, None);
+        let pos = match token.find('[') {
+            Some(pos) if next.is_none() => pos,
+            _ => return next,
+        };
+        // resolve the key of predicate first, as the array may be mounted
+        let array = if pos == 0 { self.clone() } else { self.step(&token[..pos], None) };
+        match array.node {
+            Some(Node::Real(v)) => match v.dyn_predicate(&token[pos..]) {
+                Some(i) => array.step(&i.to_string(), Some(i)),
+                None => next,
+            },
+            _ => next,
+        }
+    }
+
+    /// Resolve by array index, which count from end if negative.
+    fn path_index<I: PathIndex>(&self, i: I) -> Self {
+        match i.index_in(self.len()) {
+            Some(i) => self.step(&i.to_string(), Some(i)),
+            None => Self { node: None, ..self.clone() },
+        }
+    }
+
+    /// Read the real node, or return `rhs` for virtual parent or nothing.
+    fn read<T, F>(&self, rhs: T, f: F) -> T
+    where F: FnOnce(&'tr dyn DynValue, T) -> T
+    {
+        match self.node {
+            Some(Node::Real(v)) => f(v, rhs),
+            _ => rhs,
+        }
+    }
+}
+
+/// Path operator `/` by key or joined path.
+impl<'tr> Div<&str> for MountPtr<'tr> {
+    type Output = Self;
+    fn div(self, rhs: &str) -> Self::Output {
+        self.path_str(rhs)
+    }
+}
+
+impl<'tr> Div<String> for MountPtr<'tr> {
+    type Output = Self;
+    fn div(self, rhs: String) -> Self::Output {
+        self.path_str(rhs.as_str())
+    }
+}
+
+/// Path operator `/` by array index.
+impl<'tr, I: PathIndex> Div<I> for MountPtr<'tr> {
+    type Output = Self;
+    fn div(self, rhs: I) -> Self::Output {
+        self.path_index(rhs)
+    }
+}
+
+/// Pipe operator `|` to read the node or default, as the backend reads.
+impl<'tr> BitOr<&'tr str> for MountPtr<'tr> {
+    type Output = &'tr str;
+    fn bitor(self, rhs: &'tr str) -> Self::Output {
+        self.read(rhs, |v, rhs| v.dyn_str(rhs))
+    }
+}
+
+/// Proxy of `|` operator overload for mounted pointer.
+/// Would expand for String, i64, f64, bool.
+macro_rules! bitor_mount {
+    ($rhs:ty, $func_name:ident) => {
+        impl<'tr> BitOr<$rhs> for MountPtr<'tr> {
+            type Output = $rhs;
+            fn bitor(self, rhs: $rhs) -> Self::Output {
+                self.read(rhs, |v, rhs| v.$func_name(rhs))
+            }
+        }
+    };
+}
+
+bitor_mount!(String, dyn_string);
+bitor_mount!(i64, dyn_i64);
+bitor_mount!(f64, dyn_f64);
+bitor_mount!(bool, dyn_bool);
@@ -1,7 +1,8 @@
 // Not sub mod but seperate file for operator overload interface.
 // Used by include! macro in valueptr mod.
 
-use std::ops::{Div, BitOr, Shl, Deref, DerefMut};
+use std::ops::{Div, BitOr, Shl, Shr, Deref, DerefMut};
+use std::ops::{Range, RangeInclusive, RangeFrom, RangeTo, RangeToInclusive, RangeFull};
 
 /* ------------------------------------------------------------ */
 
@@ -19,6 +20,8 @@ where Value: ValuePath + ValueReader
 /// 
 /// First try directly json index, then try json pointer syntax, otherwise
 /// return `None` if both fail.
+/// Note that `"*"` is only a literal key, use `Wildcard` to select all
+/// children into a `ValuePtrSet`.
 ///
 /// ```rust
 /// # use serde_json::json;
@@ -31,15 +34,73 @@ where Value: ValuePath + ValueReader
 /// let p = v.path() / "a/0";
 /// assert_eq!(p.unwrap(), &v["a"][0]);
 /// ```
-impl<'tr, Value> Div<usize> for ValuePtr<'tr, Value>
-where Value: ValuePath + ValueReader
+///
+/// Index of signed integer type count from end of array if negative.
+/// As integer literal default to `i32`, `/ -1` is the last item of array.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// let v = json!({"a":["pi",null,true]});
+/// assert_eq!((v.path() / "a" / -1).unwrap(), &v["a"][2]);
+/// assert_eq!((v.path() / "a" / -3_isize).unwrap(), &v["a"][0]);
+/// assert!((v.path() / "a" / -4).is_none());
+/// assert_eq!(v.path() / "a/-3" | "", "pi");
+/// ```
+impl<'tr, Value, I> Div<I> for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader, I: PathIndex
 {
     type Output = Self;
-    fn div(self, rhs: usize) -> Self::Output {
+    fn div(self, rhs: I) -> Self::Output {
         self.path_index(rhs)
     }
 }
 
+/// Path operator `/` with range of index, select items of array into a slice
+/// view as `ValuePtrSet`, support `a..b`, `a..=b`, `a..`, `..b`, `..=b` and `..`.
+/// The range exceed the end of array is cut off.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// let v = json!({"a":["pi",null,true,"e"]});
+/// let set = v.path() / "a" / (0..2);
+/// assert_eq!(set.len(), 2);
+/// assert_eq!(set | "", vec!["pi", ""]);
+/// assert_eq!((v.path() / "a" / (2..)).len(), 2);
+/// assert_eq!((v.path() / "a" / (..=2)).len(), 3);
+/// assert_eq!((v.path() / "a" / (..)).len(), 4);
+/// assert_eq!((v.path() / "a" / (3..10)).len(), 1);
+/// ```
+macro_rules! div_range {
+    ($range:ty) => {
+        impl<'tr, Value> Div<$range> for ValuePtr<'tr, Value>
+            where Value: ValuePath + ValueReader
+        {
+            type Output = ValuePtrSet<'tr, Value>;
+            fn div(self, rhs: $range) -> Self::Output {
+                self.path_range(rhs)
+            }
+        }
+
+        impl<'tr, Value> Div<$range> for ValuePtrMut<'tr, Value>
+            where Value: ValuePath + ValueReader + ValueWriter
+        {
+            type Output = ValuePtrSetMut<'tr, Value>;
+            fn div(mut self, rhs: $range) -> Self::Output {
+                self.path_range(rhs)
+            }
+        }
+    };
+}
+
+div_range!(Range<usize>);
+div_range!(RangeInclusive<usize>);
+div_range!(RangeFrom<usize>);
+div_range!(RangeTo<usize>);
+div_range!(RangeToInclusive<usize>);
+div_range!(RangeFull);
+
 impl<'tr, Value> Div<&str> for ValuePtr<'tr, Value>
 where Value: ValuePath + ValueReader
 {
@@ -58,6 +119,91 @@ where Value: ValuePath + ValueReader
     }
 }
 
+/// Path operator `/` with precompiled `Pointer`, resolve all its tokens
+/// without parsing the path string again.
+impl<'tr, Value> Div<&Pointer> for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, rhs: &Pointer) -> Self::Output {
+        self.path_pointer(rhs)
+    }
+}
+
+/// Path operator `/` with `Wildcard`, select all children of current node,
+/// and yield `ValuePtrSet` which can continue the `/` chain.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, Wildcard};
+/// let v = json!({"service": [{"name": "a"}, {"name": "b"}, {"desc": "c"}]});
+/// let names = v.path() / "service" / Wildcard / "name";
+/// assert_eq!(names.len(), 2);
+/// assert_eq!(names | "", vec!["a", "b"]);
+/// ```
+impl<'tr, Value> Div<Wildcard> for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = ValuePtrSet<'tr, Value>;
+    fn div(self, _rhs: Wildcard) -> Self::Output {
+        self.path_any()
+    }
+}
+
+/// Path operator `/` with `Glob` pattern, select all children whose key
+/// matches, and yield `ValuePtrSet`.
+impl<'tr, Value> Div<Glob<'_>> for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = ValuePtrSet<'tr, Value>;
+    fn div(self, rhs: Glob<'_>) -> Self::Output {
+        self.path_keys(|k| rhs.is_match(k))
+    }
+}
+
+/// Path operator `/` with `Regex` (require `regex` feature), select all
+/// children whose key matches, and yield `ValuePtrSet`.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// # #[cfg(feature = "regex")] {
+/// let v = json!({"db_1": 1, "db_2": 2, "db_x": 3});
+/// let re = regex::Regex::new(r"^db_\d+$").unwrap();
+/// assert_eq!(v.path() / &re | 0, vec![1, 2]);
+/// # }
+/// ```
+#[cfg(feature = "regex")]
+impl<'tr, Value> Div<&regex::Regex> for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = ValuePtrSet<'tr, Value>;
+    fn div(self, rhs: &regex::Regex) -> Self::Output {
+        self.path_keys(|k| rhs.is_match(k))
+    }
+}
+
+/// Pipe operator `|` between pointers to fallback, yield the first pointer
+/// that is not `None`, the same as `ValuePtr::or()`.
+/// It can chain with the other `| default` to read at last.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// let v = json!({"host": {"port": 80}, "ip": "127.0.0.1"});
+/// assert_eq!(v.path()/"host"/"ip" | v.path()/"ip" | "0.0.0.0", "127.0.0.1");
+/// assert_eq!(v.path()/"ip" | v.path()/"host"/"ip" | "0.0.0.0", "127.0.0.1");
+/// assert_eq!(v.path()/"host"/"ip" | v.path()/"addr" | "0.0.0.0", "0.0.0.0");
+/// ```
+impl<'tr, Value> BitOr for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.or(rhs)
+    }
+}
+
 /// Pipe operator `|` to get string refer or default `rhs`
 /// when invalid pointer or the json type is not string.
 /// Usually used with literal `|"default"` or just simple `|""`.
@@ -221,11 +367,28 @@ where Value: ValuePath + ValueReader + ValueWriter
 /// let p = v.path_mut() / "a/0";
 /// assert_eq!(p | "", "pi");
 /// ```
-impl<'tr, Value> Div<usize> for ValuePtrMut<'tr, Value>
-where Value: ValuePath + ValueReader + ValueWriter
+///
+/// Index of signed integer type count from end of array if negative.
+/// And the special token `"-"` refer to the new item after the end of array,
+/// which is `None` until push something by operator `<<`.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// let mut v = json!({"list":[1, 2]});
+/// let _ = v.path_mut() / "list" / -1 << 20;
+/// let p = v.path_mut() / "list" / "-";
+/// assert!(p.is_none());
+/// let p = p << 3;
+/// assert_eq!(p | 0, 3);
+/// let _ = v.path_mut() / "list/-" << 4;
+/// assert_eq!(v, json!({"list":[1, 20, 3, 4]}));
+/// ```
+impl<'tr, Value, I> Div<I> for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter, I: PathIndex
 {
     type Output = Self;
-    fn div(mut self, rhs: usize) -> Self::Output {
+    fn div(mut self, rhs: I) -> Self::Output {
         self.path_index(rhs)
     }
 }
@@ -248,6 +411,15 @@ where Value: ValuePath + ValueReader + ValueWriter
     }
 }
 
+impl<'tr, Value> Div<&Pointer> for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    type Output = Self;
+    fn div(mut self, rhs: &Pointer) -> Self::Output {
+        self.path_pointer(rhs)
+    }
+}
+
 /// Pipe operator `|` to get string refer or default `rhs`.
 /// 
 /// Behaves the same as `ValuePtr | &str`, except that
@@ -425,4 +597,257 @@ where Value: From<T> + ValuePath + ValueReader + ValueWriter
     }
 }
 
+/// Operator `>>` to remove child by key or index from the node, and hand
+/// back the detached value, or `None` if not found.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// let mut v = json!({"host": {"ip": "127.0.0.1", "port": 80}, "list": [1, 2, 3]});
+/// assert_eq!(v.path_mut() / "host" >> "port", Some(json!(80)));
+/// assert_eq!(v.path_mut() / "host" >> "port", None);
+/// assert_eq!(v.path_mut() / "list" >> -1, Some(json!(3)));
+/// assert_eq!(v.path_mut() / "list" >> 0, Some(json!(1)));
+/// assert_eq!(v, json!({"host": {"ip": "127.0.0.1"}, "list": [2]}));
+/// ```
+impl<'tr, Value> Shr<&str> for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    type Output = Option<Value>;
+    fn shr(mut self, rhs: &str) -> Self::Output {
+        self.remove(rhs)
+    }
+}
+
+impl<'tr, Value> Shr<String> for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    type Output = Option<Value>;
+    fn shr(mut self, rhs: String) -> Self::Output {
+        self.remove(&rhs)
+    }
+}
+
+impl<'tr, Value, I> Shr<I> for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter, I: PathIndex
+{
+    type Output = Option<Value>;
+    fn shr(mut self, rhs: I) -> Self::Output {
+        self.remove_at(rhs)
+    }
+}
+
+/* ------------------------------------------------------------ */
+
+/// Overload `*` deref operator to treate pointer set as `Vec<&json::Value>`.
+impl<'tr, Value> Deref for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Target = Vec<&'tr Value>;
+    fn deref(&self) -> &Self::Target {
+        &self.ptrs
+    }
+}
+
+/// Iterate pointer set as each single `ValuePtr`.
+impl<'tr, Value> IntoIterator for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Item = ValuePtr<'tr, Value>;
+    type IntoIter = std::iter::Map<
+        std::iter::Zip<std::vec::IntoIter<&'tr Value>, std::iter::Repeat<ValuePtr<'tr, Value>>>,
+        fn((&'tr Value, ValuePtr<'tr, Value>)) -> ValuePtr<'tr, Value>>;
+    fn into_iter(self) -> Self::IntoIter {
+        let origin = self.origin();
+        self.ptrs.into_iter().zip(std::iter::repeat(origin))
+            .map(|(v, origin)| origin.place(v))
+    }
+}
+
+/// Path operator `/` applied to each node in pointer set, the nodes not
+/// exist are dropped, while `Wildcard` expand to all children.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, Wildcard};
+/// let v = json!({"a": [{"b": [1, 2]}, {"b": [3]}, {"c": 4}]});
+/// let set = v.path() / "a" / Wildcard / "b" / Wildcard;
+/// assert_eq!(set | 0, vec![1, 2, 3]);
+/// let set = v.path() / "a" / Wildcard / "b" / 0;
+/// assert_eq!(set | 0, vec![1, 3]);
+/// let set = v.path() / Wildcard / Wildcard / Wildcard / Wildcard;
+/// assert_eq!(set.len(), 3);
+/// ```
+impl<'tr, Value, I> Div<I> for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader, I: PathIndex
+{
+    type Output = Self;
+    fn div(self, rhs: I) -> Self::Output {
+        self.path_index(rhs)
+    }
+}
+
+impl<'tr, Value> Div<&str> for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, rhs: &str) -> Self::Output {
+        self.path_str(rhs)
+    }
+}
+
+impl<'tr, Value> Div<String> for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, rhs: String) -> Self::Output {
+        self.path_str(rhs.as_str())
+    }
+}
+
+impl<'tr, Value> Div<Wildcard> for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, _rhs: Wildcard) -> Self::Output {
+        self.path_any()
+    }
+}
+
+impl<'tr, Value> Div<Glob<'_>> for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, rhs: Glob<'_>) -> Self::Output {
+        self.path_keys(|k| rhs.is_match(k))
+    }
+}
+
+#[cfg(feature = "regex")]
+impl<'tr, Value> Div<&regex::Regex> for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Self;
+    fn div(self, rhs: &regex::Regex) -> Self::Output {
+        self.path_keys(|k| rhs.is_match(k))
+    }
+}
+
+/// Pipe operator `|` to read each node in pointer set to a `Vec`,
+/// with the same rule as single `ValuePtr` for each item.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, Wildcard};
+/// let v = json!({"a": [1, "2", "x", true]});
+/// let set = v.path() / "a" / Wildcard;
+/// assert_eq!(set.clone() | 0, vec![1, 2, 0, 1]);
+/// assert_eq!(set.clone() | "", vec!["", "2", "x", ""]);
+/// assert_eq!(set | "?".to_string(), vec!["?", "2", "x", "?"]);
+/// ```
+impl<'tr, Value> BitOr<&'tr str> for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    type Output = Vec<&'tr str>;
+    fn bitor(self, rhs: &'tr str) -> Self::Output {
+        self.get_str(rhs)
+    }
+}
+
+/// Proxy of `|` operator overload for pointer set.
+/// Would expand for String, i64, f64, bool.
+macro_rules! bitor_set {
+    ($rhs:ty, $func_name:ident) => {
+        impl<'tr, Value> BitOr<$rhs> for ValuePtrSet<'tr, Value>
+            where Value: ValuePath + ValueReader
+        {
+            type Output = Vec<$rhs>;
+            fn bitor(self, rhs: $rhs) -> Self::Output {
+                self.$func_name(rhs)
+            }
+        }
+    };
+}
+
+bitor_set!(String, get_string);
+bitor_set!(i64, get_i64);
+bitor_set!(f64, get_f64);
+bitor_set!(bool, get_bool);
+
+/* ------------------------------------------------------------ */
+
+/// Overload `*` deref operator to treate pointer set as `Vec<&mut json::Value>`.
+impl<'tr, Value> Deref for ValuePtrSetMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    type Target = Vec<&'tr mut Value>;
+    fn deref(&self) -> &Self::Target {
+        &self.ptrs
+    }
+}
+
+/// Overload `*` deref operator to treate pointer set as `Vec<&mut json::Value>`.
+impl<'tr, Value> DerefMut for ValuePtrSetMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.ptrs
+    }
+}
+
+/// Iterate mutable pointer set as each single `ValuePtrMut`.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::ValuePath;
+/// let mut v = json!({"a":[1, 2, 3, 4]});
+/// let set = v.path_mut() / "a" / (1..3);
+/// assert_eq!(set.len(), 2);
+/// for p in set {
+///     let _ = p << 0;
+/// }
+/// assert_eq!(v, json!({"a":[1, 0, 0, 4]}));
+/// assert_eq!(v.path_mut() / "a" / (2..) | 0, vec![0, 4]);
+/// ```
+impl<'tr, Value> IntoIterator for ValuePtrSetMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    type Item = ValuePtrMut<'tr, Value>;
+    type IntoIter = std::iter::Map<std::vec::IntoIter<&'tr mut Value>, fn(&'tr mut Value) -> ValuePtrMut<'tr, Value>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.ptrs.into_iter().map(|v| ValuePtrMut::untracked(Some(v)))
+    }
+}
+
+/// Pipe operator `|` to read each node in mutable pointer set to a `Vec`.
+/// Behaves the same as `ValuePtrSet`, and the `lhs` would be moved.
+impl<'tr, Value> BitOr<&'tr str> for ValuePtrSetMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    type Output = Vec<&'tr str>;
+    fn bitor(self, rhs: &'tr str) -> Self::Output {
+        self.immut().bitor(rhs)
+    }
+}
+
+/// Proxy of `|` operator overload for mutable pointer set.
+/// Would expand for String, i64, f64, bool.
+macro_rules! bitor_set_mut {
+    ($rhs:ty) => {
+        impl<'tr, Value> BitOr<$rhs> for ValuePtrSetMut<'tr, Value>
+            where Value: ValuePath + ValueReader + ValueWriter
+        {
+            type Output = Vec<$rhs>;
+            fn bitor(self, rhs: $rhs) -> Self::Output {
+                self.immut().bitor(rhs)
+            }
+        }
+    };
+}
+
+bitor_set_mut!(String);
+bitor_set_mut!(i64);
+bitor_set_mut!(f64);
+bitor_set_mut!(bool);
+
 /* ------------------------------------------------------------ */
@@ -0,0 +1,377 @@
+//! Json patch (RFC 6902) to modify a tree by a list of operations, given as
+//! json document or typed ops, and applied to json or other backend as toml.
+//! The whole patch is applied atomically, nothing changed if any op failed.
+
+use std::borrow::Cow;
+use std::fmt;
+use crate::adopter::*;
+use crate::jsonpath::deep_eq;
+use crate::matcher::KeyMatcher;
+use crate::syntax::{hop, parse_path, pointer_index, Hop, PathStep, PathSyntax};
+
+/// One operation of json patch, the paths are json pointer strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp<Value> {
+    /// Insert item to array at index, or `-` to append, or set member of
+    /// object, or replace the root for empty path.
+    Add { path: String, value: Value },
+    /// Remove the node, which must exist.
+    Remove { path: String },
+    /// Replace the node, which must exist.
+    Replace { path: String, value: Value },
+    /// Remove the node at `from` and add it to `path`.
+    Move { from: String, path: String },
+    /// Copy the node at `from` and add it to `path`.
+    Copy { from: String, path: String },
+    /// Check the node equals to value, compare number as `1 == 1.0`.
+    Test { path: String, value: Value },
+}
+
+impl<Value> PatchOp<Value> {
+    /// The name of operation, as the `op` member in json.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatchOp::Add { .. } => "add",
+            PatchOp::Remove { .. } => "remove",
+            PatchOp::Replace { .. } => "replace",
+            PatchOp::Move { .. } => "move",
+            PatchOp::Copy { .. } => "copy",
+            PatchOp::Test { .. } => "test",
+        }
+    }
+
+    /// The target path of operation.
+    pub fn path(&self) -> &str {
+        match self {
+            PatchOp::Add { path, .. } | PatchOp::Remove { path } |
+            PatchOp::Replace { path, .. } | PatchOp::Move { path, .. } |
+            PatchOp::Copy { path, .. } | PatchOp::Test { path, .. } => path,
+        }
+    }
+}
+
+/// List of patch operations, applied in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Patch<Value>(pub Vec<PatchOp<Value>>);
+
+impl<Value> Patch<Value>
+where Value: ValueWriter
+{
+    /// Parse json patch document, an array of op objects such as
+    /// `{"op": "add", "path": "/a", "value": 1}`, the values are converted
+    /// to `Value` by `ValueWriter::from_json()`.
+    pub fn from_json(doc: &serde_json::Value) -> Result<Self, PatchError> {
+        let items = doc.as_array().ok_or(PatchError {
+            op: 0,
+            path: String::new(),
+            kind: PatchErrorKind::Malformed("patch is not array"),
+        })?;
+        items.iter().enumerate()
+            .map(|(i, item)| parse_op(item).map_err(|kind| {
+                let path = item.get("path").and_then(|p| p.as_str()).unwrap_or("");
+                PatchError { op: i, path: path.to_string(), kind }
+            }))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Patch)
+    }
+}
+
+impl<Value> Patch<Value>
+where Value: ValuePath + ValueReader
+{
+    /// Serialize to json patch document, to log or ship the change.
+    /// Other scalar such as toml datetime is written as string.
+    pub fn to_json(&self) -> serde_json::Value {
+        let ops = self.0.iter().map(|op| {
+            let mut item = serde_json::Map::new();
+            item.insert("op".to_string(), op.name().into());
+            if let PatchOp::Move { from, .. } | PatchOp::Copy { from, .. } = op {
+                item.insert("from".to_string(), from.as_str().into());
+            }
+            item.insert("path".to_string(), op.path().into());
+            if let PatchOp::Add { value, .. } | PatchOp::Replace { value, .. } | PatchOp::Test { value, .. } = op {
+                item.insert("value".to_string(), to_json(value));
+            }
+            serde_json::Value::Object(item)
+        });
+        serde_json::Value::Array(ops.collect())
+    }
+}
+
+/// The source of operations for `apply_patch()`, typed ops in `Patch`,
+/// slice or vector, or json patch document in `serde_json::Value`.
+pub trait PatchDoc<Value: Clone> {
+    /// Get the operations, parse them if not typed.
+    fn patch_ops(&self) -> Result<Cow<'_, [PatchOp<Value>]>, PatchError>;
+}
+
+impl<Value: Clone> PatchDoc<Value> for Patch<Value> {
+    fn patch_ops(&self) -> Result<Cow<'_, [PatchOp<Value>]>, PatchError> {
+        Ok(Cow::Borrowed(&self.0))
+    }
+}
+
+impl<Value: Clone> PatchDoc<Value> for [PatchOp<Value>] {
+    fn patch_ops(&self) -> Result<Cow<'_, [PatchOp<Value>]>, PatchError> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl<Value: Clone> PatchDoc<Value> for Vec<PatchOp<Value>> {
+    fn patch_ops(&self) -> Result<Cow<'_, [PatchOp<Value>]>, PatchError> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl<Value> PatchDoc<Value> for serde_json::Value
+where Value: ValueWriter + Clone
+{
+    fn patch_ops(&self) -> Result<Cow<'_, [PatchOp<Value>]>, PatchError> {
+        Patch::from_json(self).map(|patch| Cow::Owned(patch.0))
+    }
+}
+
+/// Why a patch operation failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchErrorKind {
+    /// The op object in json document is malformed, with the reason.
+    Malformed(&'static str),
+    /// The path is not valid json pointer.
+    InvalidPath,
+    /// The node, or the parent to add into, does not exist.
+    NotFound,
+    /// The node is not equal to the value of `test` op.
+    TestFailed,
+    /// Move a node into its own child.
+    MoveIntoChild,
+    /// The value or change is not supported by the backend, such as null
+    /// in toml, or remove the root.
+    Unsupported,
+}
+
+impl fmt::Display for PatchErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchErrorKind::Malformed(msg) => write!(f, "malformed op, {}", msg),
+            PatchErrorKind::InvalidPath => write!(f, "invalid json pointer"),
+            PatchErrorKind::NotFound => write!(f, "path not found"),
+            PatchErrorKind::TestFailed => write!(f, "test failed"),
+            PatchErrorKind::MoveIntoChild => write!(f, "cannot move into own child"),
+            PatchErrorKind::Unsupported => write!(f, "not supported"),
+        }
+    }
+}
+
+/// Error of applying patch, the tree is left untouched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    /// Zero-based index of the failed operation in patch.
+    pub op: usize,
+    /// The failed path, which may be the `from` path of `move` or `copy`.
+    pub path: String,
+    /// Why it failed.
+    pub kind: PatchErrorKind,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "patch op {} at `{}`: {}", self.op, self.path, self.kind)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Apply json patch to the tree atomically, as all operations succeed or
+/// the tree is left untouched, and report the index of the failed op.
+/// The same json patch document can be applied to toml tree as well.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{apply_patch, PatchOp, PatchErrorKind};
+/// let mut v = json!({"host": {"port": 80}, "tags": ["a"]});
+/// let patch = json!([
+///     {"op": "test", "path": "/host/port", "value": 80},
+///     {"op": "replace", "path": "/host/port", "value": 8080},
+///     {"op": "add", "path": "/tags/-", "value": "b"},
+///     {"op": "move", "from": "/tags", "path": "/host/tags"},
+/// ]);
+/// assert!(apply_patch(&mut v, &patch).is_ok());
+/// assert_eq!(v, json!({"host": {"port": 8080, "tags": ["a", "b"]}}));
+///
+/// let ops = vec![
+///     PatchOp::Remove { path: "/host/tags".to_string() },
+///     PatchOp::Test { path: "/host/port".to_string(), value: json!(80) },
+/// ];
+/// let err = apply_patch(&mut v, &ops).unwrap_err();
+/// assert_eq!((err.op, err.kind), (1, PatchErrorKind::TestFailed));
+/// assert_eq!(v, json!({"host": {"port": 8080, "tags": ["a", "b"]}}));
+/// ```
+pub fn apply_patch<Value, P>(root: &mut Value, patch: &P) -> Result<(), PatchError>
+where Value: ValuePath + ValueReader + ValueWriter + Clone, P: PatchDoc<Value> + ?Sized
+{
+    let ops = patch.patch_ops()?;
+    let mut work = root.clone();
+    for (i, op) in ops.iter().enumerate() {
+        apply_op(&mut work, op).map_err(|(path, kind)| PatchError { op: i, path: path.to_string(), kind })?;
+    }
+    *root = work;
+    Ok(())
+}
+
+/// Failed path and why.
+type Failure<'a> = (&'a str, PatchErrorKind);
+
+fn apply_op<'a, Value>(root: &mut Value, op: &'a PatchOp<Value>) -> Result<(), Failure<'a>>
+where Value: ValuePath + ValueReader + ValueWriter + Clone
+{
+    match op {
+        PatchOp::Add { path, value } => add(root, path, value.clone()).map_err(|kind| (path.as_str(), kind)),
+        PatchOp::Remove { path } => remove(root, path).map(|_| ()).map_err(|kind| (path.as_str(), kind)),
+        PatchOp::Replace { path, value } => {
+            let steps = steps(path).map_err(|kind| (path.as_str(), kind))?;
+            let node = node_mut(root, &steps).ok_or((path.as_str(), PatchErrorKind::NotFound))?;
+            *node = value.clone();
+            Ok(())
+        }
+        PatchOp::Move { from, path } => {
+            if path == from {
+                return node(root, from).map(|_| ()).map_err(|kind| (from.as_str(), kind));
+            }
+            if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                return Err((path.as_str(), PatchErrorKind::MoveIntoChild));
+            }
+            let value = remove(root, from).map_err(|kind| (from.as_str(), kind))?;
+            add(root, path, value).map_err(|kind| (path.as_str(), kind))
+        }
+        PatchOp::Copy { from, path } => {
+            let value = node(root, from).map_err(|kind| (from.as_str(), kind))?.clone();
+            add(root, path, value).map_err(|kind| (path.as_str(), kind))
+        }
+        PatchOp::Test { path, value } => {
+            let node = node(root, path).map_err(|kind| (path.as_str(), kind))?;
+            if deep_eq(node, value) { Ok(()) } else { Err((path.as_str(), PatchErrorKind::TestFailed)) }
+        }
+    }
+}
+
+/// Parse json pointer to steps.
+fn steps(path: &str) -> Result<Vec<PathStep>, PatchErrorKind> {
+    parse_path(path, PathSyntax::Pointer).map_err(|_| PatchErrorKind::InvalidPath)
+}
+
+/// Get the node at path.
+fn node<'tr, Value>(root: &'tr Value, path: &str) -> Result<&'tr Value, PatchErrorKind>
+where Value: ValuePath + ValueReader
+{
+    let mut node = root;
+    for step in steps(path)?.iter() {
+        node = match hop(node, step, KeyMatcher::Exact) {
+            Some(Hop::Key(key)) => node.get_key(&key),
+            Some(Hop::Index(i)) => node.get_index(i),
+            None => None,
+        }.ok_or(PatchErrorKind::NotFound)?;
+    }
+    Ok(node)
+}
+
+/// Get the mutable node by steps.
+fn node_mut<'tr, Value>(root: &'tr mut Value, steps: &[PathStep]) -> Option<&'tr mut Value>
+where Value: ValuePath + ValueReader
+{
+    let mut node = root;
+    for step in steps {
+        node = match hop(node, step, KeyMatcher::Exact)? {
+            Hop::Key(key) => node.get_key_mut(&key)?,
+            Hop::Index(i) => node.get_index_mut(i)?,
+        };
+    }
+    Some(node)
+}
+
+/// Add value at path, the parent must exist.
+fn add<Value>(root: &mut Value, path: &str, value: Value) -> Result<(), PatchErrorKind>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    let steps = steps(path)?;
+    let Some((last, parent)) = steps.split_last() else {
+        *root = value;
+        return Ok(());
+    };
+    let node = node_mut(root, parent).ok_or(PatchErrorKind::NotFound)?;
+    let token = last.token();
+    match node.get_kind() {
+        NodeKind::Array if token == "-" => {
+            node.push_array(value);
+            Ok(())
+        }
+        NodeKind::Array => match pointer_index(&token) {
+            Some(i) if i <= node.get_len() => {
+                if node.insert_array(i, value) { Ok(()) } else { Err(PatchErrorKind::Unsupported) }
+            }
+            _ => Err(PatchErrorKind::NotFound),
+        },
+        NodeKind::Object => {
+            node.push_object(token, value);
+            Ok(())
+        }
+        _ => Err(PatchErrorKind::NotFound),
+    }
+}
+
+/// Remove the node at path and return it.
+fn remove<Value>(root: &mut Value, path: &str) -> Result<Value, PatchErrorKind>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    let steps = steps(path)?;
+    let (last, parent) = steps.split_last().ok_or(PatchErrorKind::Unsupported)?;
+    let node = node_mut(root, parent).ok_or(PatchErrorKind::NotFound)?;
+    match hop(node, last, KeyMatcher::Exact) {
+        Some(Hop::Key(key)) => node.remove_key(&key),
+        Some(Hop::Index(i)) => node.remove_index(i),
+        None => None,
+    }.ok_or(PatchErrorKind::NotFound)
+}
+
+/// Parse one op object of json patch document.
+fn parse_op<Value>(item: &serde_json::Value) -> Result<PatchOp<Value>, PatchErrorKind>
+where Value: ValueWriter
+{
+    let member = |name: &str| item.get(name).and_then(|x| x.as_str()).map(|x| x.to_string());
+    let path = member("path").ok_or(PatchErrorKind::Malformed("missing `path` string"))?;
+    let from = || member("from").ok_or(PatchErrorKind::Malformed("missing `from` string"));
+    let value = || {
+        let json = item.get("value").ok_or(PatchErrorKind::Malformed("missing `value`"))?;
+        Value::from_json(json).ok_or(PatchErrorKind::Unsupported)
+    };
+    match item.get("op").and_then(|x| x.as_str()) {
+        Some("add") => Ok(PatchOp::Add { path, value: value()? }),
+        Some("remove") => Ok(PatchOp::Remove { path }),
+        Some("replace") => Ok(PatchOp::Replace { path, value: value()? }),
+        Some("move") => Ok(PatchOp::Move { from: from()?, path }),
+        Some("copy") => Ok(PatchOp::Copy { from: from()?, path }),
+        Some("test") => Ok(PatchOp::Test { path, value: value()? }),
+        Some(_) => Err(PatchErrorKind::Malformed("unknown `op`")),
+        None => Err(PatchErrorKind::Malformed("missing `op` string")),
+    }
+}
+
+/// Convert node of any backend to json value.
+fn to_json<Value>(node: &Value) -> serde_json::Value
+where Value: ValuePath + ValueReader
+{
+    use serde_json::Value as Json;
+    match node.get_kind() {
+        NodeKind::Array => Json::Array((0..node.get_len()).filter_map(|i| node.get_index(i)).map(to_json).collect()),
+        NodeKind::Object => Json::Object(node.get_keys().into_iter()
+            .filter_map(|k| node.get_key(k).map(|v| (k.to_string(), to_json(v))))
+            .collect()),
+        _ => match node.get_scalar() {
+            Some(Scalar::Null) => Json::Null,
+            Some(Scalar::Bool(b)) => Json::Bool(b),
+            Some(Scalar::Int(i)) => Json::from(i),
+            Some(Scalar::Float(f)) => Json::from(f),
+            Some(Scalar::Str(s)) => Json::from(s),
+            None => Json::String(node.get_string(String::new())),
+        },
+    }
+}
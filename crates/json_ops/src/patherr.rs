@@ -0,0 +1,213 @@
+//! Explain why a path resolved to nothing.
+//! The pointer only records the node where it failed and the failing token
+//! in a small copyable buffer, then build `PathError` lazily when asked.
+//! Token too long for the buffer is searched for near-miss keys at once,
+//! and only their positions among the keys are kept.
+
+use std::fmt;
+use crate::adopter::*;
+
+/// Why a path segment failed to resolve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathErrorKind {
+    /// The key not found in object node.
+    MissingKey,
+    /// The index beyond the array with `len` items.
+    IndexOutOfRange { len: usize },
+    /// The token is not a number while the node is array.
+    NotIndex,
+    /// The node is scalar, cannot go further.
+    NotContainer,
+}
+
+impl fmt::Display for PathErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathErrorKind::MissingKey => write!(f, "key not found"),
+            PathErrorKind::IndexOutOfRange { len } => write!(f, "index out of range for length {}", len),
+            PathErrorKind::NotIndex => write!(f, "not an array index"),
+            PathErrorKind::NotContainer => write!(f, "not a container"),
+        }
+    }
+}
+
+/// Error of resolving path, reported by `ValuePtr::error()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathError {
+    /// Zero-based index of the failed segment, counting each `/` operand
+    /// and each token of joined path from where the `/` chain begins.
+    pub segment: usize,
+    /// The failed token, very long token is truncated and end with `...`,
+    /// but its near-miss keys are still searched by the full token.
+    pub token: String,
+    /// Why it failed.
+    pub kind: PathErrorKind,
+    /// The kind of node where the token applied to.
+    pub found: NodeKind,
+    /// Near-miss keys of the object node, the closest first.
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "segment {} `{}`: {} at {} node", self.segment, self.token, self.kind, self.found)?;
+        if !self.suggestions.is_empty() {
+            let keys: Vec<String> = self.suggestions.iter().map(|k| format!("`{}`", k)).collect();
+            write!(f, ", did you mean {}?", keys.join(" or "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Why entry mode failed to create missing node, reported by
+/// `ValuePtrMut::entry_error()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryError {
+    /// The index is too far beyond the end of array with `len` items,
+    /// that would pad more than `limit` blank items.
+    TooFar { index: usize, len: usize, limit: usize },
+    /// The token cannot be created in the node of `found` kind, such as
+    /// scalar, or index in object that is not blank.
+    Mismatch { token: String, found: NodeKind },
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryError::TooFar { index, len, limit } =>
+                write!(f, "index {} is too far beyond the end of array with length {}, limit {} blank items to pad", index, len, limit),
+            EntryError::Mismatch { token, found } =>
+                write!(f, "cannot create `{}` in {} node", token, found),
+        }
+    }
+}
+
+impl std::error::Error for EntryError {}
+
+const TOKEN_CAP: usize = 18;
+const TRUNCATED: u8 = 0x80;
+const NEAR_CAP: usize = 3;
+const NO_NEAR: u32 = u32::MAX;
+
+/// Copyable inline buffer to save the failed token, the high bit of `len`
+/// tells it is truncated. For truncated token, `near` saves the positions
+/// of near-miss keys in `get_keys()` of the failed node.
+#[derive(Clone, Copy)]
+pub(crate) struct TokenBuf {
+    len: u8,
+    buf: [u8; TOKEN_CAP],
+    near: [u32; NEAR_CAP],
+}
+
+impl TokenBuf {
+    /// Save `token` failed to resolve from `parent` node.
+    pub(crate) fn new<Value>(parent: &Value, token: &str) -> Self
+    where Value: ValuePath
+    {
+        let mut len = token.len().min(TOKEN_CAP);
+        while !token.is_char_boundary(len) {
+            len -= 1;
+        }
+        let mut buf = [0; TOKEN_CAP];
+        buf[..len].copy_from_slice(&token.as_bytes()[..len]);
+        let mut near = [NO_NEAR; NEAR_CAP];
+        if len == token.len() {
+            return Self { len: len as u8, buf, near };
+        }
+
+        // the full token is not kept, so search the keys right now
+        for (slot, pos) in near.iter_mut().zip(near_keys(token, &parent.get_keys())) {
+            *slot = pos as u32;
+        }
+        Self { len: len as u8 | TRUNCATED, buf, near }
+    }
+
+    /// Empty buffer for pointer not failed.
+    pub(crate) fn empty() -> Self {
+        Self { len: 0, buf: [0; TOKEN_CAP], near: [NO_NEAR; NEAR_CAP] }
+    }
+
+    fn truncated(&self) -> bool {
+        self.len & TRUNCATED != 0
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.buf[..(self.len & !TRUNCATED) as usize]).unwrap_or_default()
+    }
+}
+
+/// Build the error of pointer failed to resolve `token` from `parent` node
+/// at `segment`, only now to inspect the node and search near-miss keys.
+pub(crate) fn explain<Value>(parent: &Value, segment: u32, buf: &TokenBuf) -> PathError
+where Value: ValuePath + ValueReader
+{
+    let truncated = buf.truncated();
+    let token = buf.as_str();
+    let found = parent.get_kind();
+    let kind = match found {
+        NodeKind::Object => PathErrorKind::MissingKey,
+        NodeKind::Array if token.parse::<isize>().is_ok() =>
+            PathErrorKind::IndexOutOfRange { len: parent.get_len() },
+        NodeKind::Array => PathErrorKind::NotIndex,
+        NodeKind::Other if parent.get_scalar().is_none() => PathErrorKind::MissingKey,
+        _ => PathErrorKind::NotContainer,
+    };
+
+    let mut suggestions = Vec::new();
+    if kind == PathErrorKind::MissingKey {
+        let keys = parent.get_keys();
+        let near = match truncated {
+            true => buf.near.iter().take_while(|&&pos| pos != NO_NEAR).map(|&pos| pos as usize).collect(),
+            false => near_keys(token, &keys),
+        };
+        suggestions = near.into_iter().filter_map(|pos| keys.get(pos)).map(|key| key.to_string()).collect();
+    }
+
+    let mut token = token.to_string();
+    if truncated {
+        token.push_str("...");
+    }
+    PathError { segment: segment as usize, token, kind, found, suggestions }
+}
+
+/// Select at most 3 keys similar to token, by edit distance or ignore case,
+/// return their positions in `keys`.
+fn near_keys(token: &str, keys: &[&str]) -> Vec<usize> {
+    let limit = (token.chars().count() / 3).max(1);
+    let mut near: Vec<(usize, &str, usize)> = keys.iter().enumerate()
+        .filter_map(|(pos, &key)| {
+            if key.eq_ignore_ascii_case(token) {
+                return Some((0, key, pos));
+            }
+            let dist = edit_distance(token, key);
+            if dist <= limit { Some((dist, key, pos)) } else { None }
+        })
+        .collect();
+    near.sort();
+    near.into_iter().take(NEAR_CAP).map(|(_, _, pos)| pos).collect()
+}
+
+/// Edit distance by chars, where swap two adjacent chars count as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
@@ -0,0 +1,463 @@
+//! Json pointer (RFC 6901) string helpers, to escape token and locate node.
+//! And the precompiled `Pointer` type to resolve the same path many times.
+
+use std::borrow::Cow;
+use std::fmt;
+use crate::adopter::*;
+use crate::valueptr::{ValuePtr, ValuePtrMut};
+use crate::jsonpath::deep_eq;
+
+/// One unescaped token of precompiled `Pointer`, with the array index hint
+/// parsed in advance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PointerToken {
+    key: String,
+    index: Option<isize>,
+}
+
+/// Precompiled path, parsed once and used to resolve many times, as
+/// `v.path() / &ptr` or `ptr.resolve(&v)`, avoid to split and unescape the
+/// path string each time as `pathto()`.
+///
+/// It follows the same syntax as `pathto()`, and can also be constructed
+/// from static unescaped tokens in const context, so can be stored in `static`.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, Pointer};
+/// static PORT: Pointer = Pointer::from_static(&["host", "port"], &[None, None]);
+///
+/// let mut v = json!({"host": {"port": 8080, "protocol": ["tcp", "udp"]}});
+/// assert_eq!(v.path() / &PORT | 0, 8080);
+/// assert_eq!(PORT.resolve(&v) | 0, 8080);
+///
+/// let proto = Pointer::new("/host/protocol/-1");
+/// assert_eq!(v.path() / &proto | "", "udp");
+/// let _ = proto.resolve_mut(&mut v) << "quic";
+/// assert_eq!(v.pointer("/host/protocol/1").unwrap(), "quic");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pointer {
+    tokens: Tokens,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tokens {
+    Static(&'static [&'static str], &'static [Option<isize>]),
+    Owned(Vec<PointerToken>),
+}
+
+impl Pointer {
+    /// Parse path string in the same syntax as `pathto()`, split by both `/`
+    /// and `.` but not inside `[...]` of predicate, the leading `/` is optional.
+    pub fn new(p: &str) -> Self {
+        let tokens: Vec<PointerToken> = split_loose(p)
+            .map(|key| {
+                let index = key.parse::<isize>().ok();
+                PointerToken { key, index }
+            })
+            .collect();
+        Self { tokens: Tokens::Owned(tokens) }
+    }
+
+    /// Create pointer from static unescaped tokens in const context, with
+    /// array index hint of each token, `Some` for numeric token such as `-1`.
+    /// Both slices must have the same length, as `path!` macro builds them.
+    pub const fn from_static(tokens: &'static [&'static str], indexes: &'static [Option<isize>]) -> Self {
+        assert!(tokens.len() == indexes.len(), "each static token needs an index hint");
+        Self { tokens: Tokens::Static(tokens, indexes) }
+    }
+
+    /// Number of tokens.
+    pub fn len(&self) -> usize {
+        match &self.tokens {
+            Tokens::Static(tokens, _) => tokens.len(),
+            Tokens::Owned(tokens) => tokens.len(),
+        }
+    }
+
+    /// If has no token, refer to the root itself.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate the unescaped tokens.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        (0..self.len()).map(|i| self.step(i).0)
+    }
+
+    /// Get the unescaped token at `i` with array index hint.
+    pub(crate) fn step(&self, i: usize) -> (&str, Option<isize>) {
+        match &self.tokens {
+            Tokens::Static(tokens, indexes) => (tokens[i], indexes[i]),
+            Tokens::Owned(tokens) => (tokens[i].key.as_str(), tokens[i].index),
+        }
+    }
+
+    /// Resolve from the value as root, the same as `v.path() / &self`.
+    pub fn resolve<'tr, Value>(&self, v: &'tr Value) -> ValuePtr<'tr, Value>
+    where Value: ValuePath + ValueReader
+    {
+        v.path() / self
+    }
+
+    /// Resolve from the value as root for mutable pointer,
+    /// the same as `v.path_mut() / &self`.
+    pub fn resolve_mut<'tr, Value>(&self, v: &'tr mut Value) -> ValuePtrMut<'tr, Value>
+    where Value: ValuePath + ValueReader + ValueWriter
+    {
+        v.path_mut() / self
+    }
+}
+
+impl From<&str> for Pointer {
+    fn from(p: &str) -> Self {
+        Self::new(p)
+    }
+}
+
+/// Display as canonical json pointer string.
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens: Vec<&str> = self.keys().collect();
+        f.write_str(&join_pointer(&tokens))
+    }
+}
+
+/// Split loose path by `/` or `.`, but not inside `[...]` of predicate.
+pub(crate) struct LooseTokens<'a> {
+    pub(crate) rest: Option<&'a str>,
+}
+
+impl<'a> Iterator for LooseTokens<'a> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let bytes = rest.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'/' | b'.' => {
+                    self.rest = Some(&rest[i+1..]);
+                    return Some(&rest[..i]);
+                }
+                b'[' => i += rest[i..].find(']').unwrap_or(0),
+                _ => {}
+            }
+            i += 1;
+        }
+        self.rest = None;
+        Some(rest)
+    }
+}
+
+/// Split path in the syntax of `pathto()` to unescaped tokens, by `/` or `.`
+/// but not inside `[...]` of predicate, the leading `/` is optional.
+pub(crate) fn split_loose(p: &str) -> impl Iterator<Item = String> + '_ {
+    let p = p.strip_prefix('/').unwrap_or(p);
+    (LooseTokens { rest: Some(p) }).map(|x| x.replace("~1", "/").replace("~0", "~"))
+}
+
+/// Escape a path token as json pointer syntax, `~` to `~0` and `/` to `~1`.
+pub(crate) fn escape_token(token: &str) -> Cow<'_, str> {
+    if token.contains(['~', '/']) {
+        Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+    }
+    else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Join path tokens to canonical json pointer string, such as `/a/0/b`,
+/// and empty string for no token that refer to the root itself.
+pub(crate) fn join_pointer<S: fmt::Display>(tokens: &[S]) -> String {
+    let mut out = String::new();
+    for token in tokens {
+        out.push('/');
+        out.push_str(&escape_token(&token.to_string()));
+    }
+    out
+}
+
+/// Resolved path token, the key of object or index of array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    Key(String),
+    Index(usize),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Key(key) => f.write_str(key),
+            Token::Index(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+/// What to search by `find_paths()`.
+#[derive(Debug, Clone, Copy)]
+pub enum Needle<'a, Value> {
+    /// The node by reference identity, which is obtained from the same tree.
+    Node(&'a Value),
+    /// Any node equals to this value, compare scalar and structure
+    /// as the `==` in JSONPath filter, so `1` equals to `1.0`.
+    Equal(&'a Value),
+}
+
+/// Find all paths where the needle occurs in the tree from `root`, as json
+/// pointer strings in depth-first order, array items before object keys.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{find_paths, Needle};
+/// let v = json!({"a": {"port": 80}, "b": [80, {"port": 8080}]});
+/// assert_eq!(find_paths(&v, Needle::Equal(&json!(80))), vec!["/a/port", "/b/0"]);
+/// assert_eq!(find_paths(&v, Needle::Node(&v["b"][1])), vec!["/b/1"]);
+/// ```
+pub fn find_paths<Value>(root: &Value, needle: Needle<'_, Value>) -> Vec<String>
+where Value: ValuePath + ValueReader
+{
+    let mut found = Vec::new();
+    find_in(root, &needle, &mut Vec::new(), &mut found);
+    found
+}
+
+fn find_in<Value>(node: &Value, needle: &Needle<'_, Value>, trail: &mut Vec<String>, found: &mut Vec<String>)
+where Value: ValuePath + ValueReader
+{
+    let hit = match needle {
+        Needle::Node(target) => std::ptr::eq(node, *target),
+        Needle::Equal(target) => deep_eq(node, *target),
+    };
+    if hit {
+        found.push(join_pointer(trail));
+    }
+    for i in 0..node.get_len() {
+        if let Some(child) = node.get_index(i) {
+            trail.push(i.to_string());
+            find_in(child, needle, trail, found);
+            trail.pop();
+        }
+    }
+    for key in node.get_keys() {
+        if let Some(child) = node.get_key(key) {
+            trail.push(key.to_string());
+            find_in(child, needle, trail, found);
+            trail.pop();
+        }
+    }
+}
+
+/// Build a static `Pointer` from path literal at compile time, in the same
+/// syntax as `pathto()`, split by both `/` and `.` but not inside `[...]`,
+/// the leading `/` is optional.
+/// The path is split and unescaped in const context, and invalid escape such as
+/// `~2` is a compile error. It yields `&'static Pointer` used as `v.path() / path!(...)`.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, path};
+/// let v = json!({"usr": {"lib": [0, 1, {"name": "libc", "a/b": true}]}});
+/// assert_eq!(v.path() / path!("usr/lib/2/name") | "", "libc");
+/// assert_eq!(v.path() / path!("/usr.lib.-1/a~1b") | false, true);
+/// assert_eq!(path!("usr/lib/2/name").len(), 4);
+/// ```
+///
+/// ```compile_fail
+/// let ptr = json_ops::path!("usr/lib/~2");
+/// ```
+#[macro_export]
+macro_rules! path {
+    ($p:expr) => {{
+        const P: &str = $p;
+        const N: usize = $crate::__path::token_count(P);
+        const LEN: usize = $crate::__path::text_len(P);
+        const TEXT: [u8; LEN] = $crate::__path::pack::<LEN>(P);
+        const ENDS: [usize; N] = $crate::__path::ends::<N>(P);
+        const TOKENS: [&str; N] = $crate::__path::tokens::<N>(&TEXT, &ENDS);
+        const INDEXES: [Option<isize>; N] = $crate::__path::indexes::<N>(&TOKENS);
+        static PTR: $crate::Pointer = $crate::Pointer::from_static(&TOKENS, &INDEXES);
+        &PTR
+    }};
+}
+
+/// Const helpers for `path!` macro, split and unescape path in const context.
+#[doc(hidden)]
+pub mod macros {
+    /// Skip the optional leading `/`.
+    const fn start(p: &str) -> usize {
+        if !p.is_empty() && p.as_bytes()[0] == b'/' { 1 } else { 0 }
+    }
+
+    /// Check the separator `/` or `.` at `i` splits token, not inside `[...]`
+    /// of predicate, the same as `LooseTokens` does.
+    const fn is_sep(bytes: &[u8], i: usize) -> bool {
+        if bytes[i] != b'/' && bytes[i] != b'.' {
+            return false;
+        }
+        let mut j = 0;
+        while j < i {
+            if bytes[j] == b'[' {
+                let mut k = j + 1;
+                while k < bytes.len() && bytes[k] != b']' {
+                    k += 1;
+                }
+                if k < bytes.len() {
+                    if k > i {
+                        return false;
+                    }
+                    j = k;
+                }
+            }
+            j += 1;
+        }
+        true
+    }
+
+    /// Check the escape at `i`, return the unescaped byte.
+    const fn unescape(bytes: &[u8], i: usize) -> u8 {
+        if i + 1 < bytes.len() {
+            match bytes[i + 1] {
+                b'0' => return b'~',
+                b'1' => return b'/',
+                _ => {}
+            }
+        }
+        panic!("invalid escape in path, `~` must be followed by `0` or `1`");
+    }
+
+    /// Number of tokens in path.
+    pub const fn token_count(p: &str) -> usize {
+        let bytes = p.as_bytes();
+        let mut count = 1;
+        let mut i = start(p);
+        while i < bytes.len() {
+            if is_sep(bytes, i) {
+                count += 1;
+            }
+            i += 1;
+        }
+        count
+    }
+
+    /// Total length of all unescaped tokens, also validate escapes.
+    pub const fn text_len(p: &str) -> usize {
+        let bytes = p.as_bytes();
+        let mut len = 0;
+        let mut i = start(p);
+        while i < bytes.len() {
+            if bytes[i] == b'~' {
+                unescape(bytes, i);
+                i += 1;
+            }
+            if !is_sep(bytes, i) {
+                len += 1;
+            }
+            i += 1;
+        }
+        len
+    }
+
+    /// All unescaped tokens packed together without separator.
+    pub const fn pack<const LEN: usize>(p: &str) -> [u8; LEN] {
+        let bytes = p.as_bytes();
+        let mut text = [0; LEN];
+        let mut len = 0;
+        let mut i = start(p);
+        while i < bytes.len() {
+            if bytes[i] == b'~' {
+                text[len] = unescape(bytes, i);
+                len += 1;
+                i += 2;
+                continue;
+            }
+            if !is_sep(bytes, i) {
+                text[len] = bytes[i];
+                len += 1;
+            }
+            i += 1;
+        }
+        text
+    }
+
+    /// The end offset of each token in packed text.
+    pub const fn ends<const N: usize>(p: &str) -> [usize; N] {
+        let bytes = p.as_bytes();
+        let mut ends = [0; N];
+        let mut len = 0;
+        let mut n = 0;
+        let mut i = start(p);
+        while i < bytes.len() {
+            if is_sep(bytes, i) {
+                ends[n] = len;
+                n += 1;
+            }
+            else {
+                len += 1;
+            }
+            i += if bytes[i] == b'~' { 2 } else { 1 };
+        }
+        ends[n] = len;
+        ends
+    }
+
+    /// Slice each token from packed text.
+    pub const fn tokens<const N: usize>(text: &'static [u8], ends: &[usize; N]) -> [&'static str; N] {
+        let mut tokens = [""; N];
+        let mut begin = 0;
+        let mut n = 0;
+        while n < N {
+            let (head, _) = text.split_at(ends[n]);
+            let (_, token) = head.split_at(begin);
+            tokens[n] = match std::str::from_utf8(token) {
+                Ok(token) => token,
+                Err(_) => panic!("invalid utf8 in path"),
+            };
+            begin = ends[n];
+            n += 1;
+        }
+        tokens
+    }
+
+    /// Parse each token as array index, the same as `str::parse::<isize>()`.
+    pub const fn indexes<const N: usize>(tokens: &[&str; N]) -> [Option<isize>; N] {
+        let mut indexes = [None; N];
+        let mut n = 0;
+        while n < N {
+            indexes[n] = parse_index(tokens[n].as_bytes());
+            n += 1;
+        }
+        indexes
+    }
+
+    /// Parse optional sign and decimal digits, `None` if overflow.
+    const fn parse_index(bytes: &[u8]) -> Option<isize> {
+        let (negative, mut i) = match bytes {
+            [b'-', ..] => (true, 1),
+            [b'+', ..] => (false, 1),
+            _ => (false, 0),
+        };
+        if i == bytes.len() {
+            return None;
+        }
+        // accumulate toward the sign, so `isize::MIN` is not overflow
+        let mut value: isize = 0;
+        while i < bytes.len() {
+            if !bytes[i].is_ascii_digit() {
+                return None;
+            }
+            let digit = (bytes[i] - b'0') as isize;
+            let next = match value.checked_mul(10) {
+                Some(value) => if negative { value.checked_sub(digit) } else { value.checked_add(digit) },
+                None => None,
+            };
+            value = match next {
+                Some(next) => next,
+                None => return None,
+            };
+            i += 1;
+        }
+        Some(value)
+    }
+}
@@ -0,0 +1,114 @@
+//! Relative JSON Pointer (draft-bhutton-relative-json-pointer) to evaluate
+//! from the position of existing pointer, such as `1/name` or `0#`.
+
+use std::fmt;
+use crate::syntax::*;
+
+/// The result of relative pointer, a node pointer, or the key or index
+/// of the node for the `#` form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Relative<P> {
+    Ptr(P),
+    Key(String),
+    Index(usize),
+}
+
+impl<P> Relative<P> {
+    /// Get the pointer if it is not the `#` form.
+    pub fn ptr(self) -> Option<P> {
+        match self {
+            Relative::Ptr(p) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+/// Error when parse or evaluate relative pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelativeError {
+    /// Invalid relative pointer string at byte offset `pos`.
+    Syntax { pos: usize, msg: String },
+    /// The pointer refers to nothing to begin with.
+    NoNode,
+    /// Move up beyond the root where the `/` chain begins.
+    AboveRoot,
+    /// Index manipulation applied to node not an array item.
+    NotArrayItem,
+    /// Index manipulation beyond the array.
+    IndexOutOfRange,
+    /// The `#` form applied to root node which has no key or index.
+    NoName,
+    /// Mutable pointer cannot move up, but can resolve this absolute
+    /// json `pointer` from the root instead.
+    Upward { pointer: String },
+}
+
+impl fmt::Display for RelativeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelativeError::Syntax { pos, msg } => write!(f, "invalid relative pointer at {}: {}", pos, msg),
+            RelativeError::NoNode => write!(f, "pointer refers to nothing"),
+            RelativeError::AboveRoot => write!(f, "move up beyond the root"),
+            RelativeError::NotArrayItem => write!(f, "index manipulation on node not in array"),
+            RelativeError::IndexOutOfRange => write!(f, "index manipulation out of range"),
+            RelativeError::NoName => write!(f, "root node has no key or index"),
+            RelativeError::Upward { pointer } =>
+                write!(f, "mutable pointer cannot move up, resolve `{}` from root instead", pointer),
+        }
+    }
+}
+
+impl std::error::Error for RelativeError {}
+
+/// What to get after moving to the origin node.
+pub(crate) enum Tail {
+    /// The `#` form, get key or index.
+    Name,
+    /// Json pointer to resolve from origin node.
+    Steps(Vec<PathStep>),
+}
+
+/// Parsed relative pointer.
+pub(crate) struct RelativePointer {
+    /// Levels to move up.
+    pub(crate) up: usize,
+    /// Index manipulation to sibling item.
+    pub(crate) shift: isize,
+    pub(crate) tail: Tail,
+}
+
+/// Parse relative pointer string.
+pub(crate) fn parse_relative(rel: &str) -> Result<RelativePointer, RelativeError> {
+    let err = |pos: usize, msg: &str| RelativeError::Syntax { pos, msg: msg.to_string() };
+    let (up, mut pos) = parse_integer(rel, 0).ok_or_else(|| err(0, "expect non-negative integer"))?;
+
+    let mut shift = 0;
+    if let Some(sign @ (b'+' | b'-')) = rel.as_bytes().get(pos) {
+        let (n, end) = parse_integer(rel, pos + 1)
+            .filter(|(n, _)| *n > 0)
+            .ok_or_else(|| err(pos + 1, "expect positive integer after sign"))?;
+        let n = isize::try_from(n).map_err(|_| err(pos + 1, "index too large"))?;
+        shift = if *sign == b'-' { -n } else { n };
+        pos = end;
+    }
+
+    let tail = if &rel[pos..] == "#" {
+        Tail::Name
+    }
+    else {
+        let steps = parse_path(&rel[pos..], PathSyntax::Pointer)
+            .map_err(|e| err(pos + e.pos, &e.msg))?;
+        Tail::Steps(steps)
+    };
+    Ok(RelativePointer { up, shift, tail })
+}
+
+/// Parse integer without leading zero from `pos`, return it and the end position.
+fn parse_integer(s: &str, pos: usize) -> Option<(usize, usize)> {
+    let digits = s[pos..].bytes().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || (digits > 1 && s.as_bytes()[pos] == b'0') {
+        return None;
+    }
+    let end = pos + digits;
+    s[pos..end].parse().ok().map(|n| (n, end))
+}
@@ -0,0 +1,228 @@
+//! Selectable path syntax for `pathto_with()` resolution.
+//! The default `Loose` syntax is the same as `pathto()`, other syntax are
+//! parsed to steps first, and report error if the path string is invalid.
+
+use std::fmt;
+use crate::adopter::*;
+use crate::matcher::KeyMatcher;
+
+/// The syntax to parse path string in `pathto_with()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathSyntax {
+    /// Split by both `/` and `.`, and try key then index for each token,
+    /// the leading `/` is optional. It is the syntax of `pathto()`.
+    #[default]
+    Loose,
+    /// Strict json pointer (RFC 6901) as `/a/0/b`, only split by `/`,
+    /// `~0` and `~1` escape for `~` and `/`, index only for array node.
+    Pointer,
+    /// Dotted key and bracket index, as `a.b[0].c`, negative index allowed.
+    Dotted,
+    /// Dotted syntax with quoted key in brackets, as `a["x.y"][0]` or `a['x.y']`,
+    /// backslash escape quote or backslash itself in quoted key.
+    Bracket,
+}
+
+impl fmt::Display for PathSyntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PathSyntax::Loose => "loose",
+            PathSyntax::Pointer => "pointer",
+            PathSyntax::Dotted => "dotted",
+            PathSyntax::Bracket => "bracket",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Error when parse path string in some `PathSyntax`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathSyntaxError {
+    /// The syntax used to parse.
+    pub syntax: PathSyntax,
+    /// Byte offset in the path string where the error found.
+    pub pos: usize,
+    /// What is wrong there.
+    pub msg: String,
+}
+
+impl fmt::Display for PathSyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} path at {}: {}", self.syntax, self.pos, self.msg)
+    }
+}
+
+impl std::error::Error for PathSyntaxError {}
+
+/// One step of parsed path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PathStep {
+    /// Key of object only.
+    Key(String),
+    /// Index of array only, count from end if negative.
+    Index(isize),
+    /// Json pointer token, as key for object or strict index for array.
+    Token(String),
+}
+
+impl PathStep {
+    /// The token text to report or record.
+    pub(crate) fn token(&self) -> String {
+        match self {
+            PathStep::Key(key) | PathStep::Token(key) => key.clone(),
+            PathStep::Index(i) => i.to_string(),
+        }
+    }
+}
+
+/// Where a step goes from a node, decided by immutable node before move.
+/// The key is the actual key matched in node.
+pub(crate) enum Hop {
+    Key(String),
+    Index(usize),
+}
+
+/// Find the hop of step from node, `None` if not exist.
+pub(crate) fn hop<Value>(node: &Value, step: &PathStep, matcher: KeyMatcher) -> Option<Hop>
+where Value: ValuePath + ValueReader
+{
+    match step {
+        PathStep::Key(key) => matcher.actual_key(node, key).map(Hop::Key),
+        PathStep::Index(i) => i.index_in(node.get_len())
+            .filter(|i| node.get_index(*i).is_some())
+            .map(Hop::Index),
+        PathStep::Token(token) => {
+            if node.get_kind() != NodeKind::Array {
+                if let Some(key) = matcher.actual_key(node, token) {
+                    return Some(Hop::Key(key));
+                }
+            }
+            pointer_index(token).filter(|i| node.get_index(*i).is_some()).map(Hop::Index)
+        }
+    }
+}
+
+/// Strict array index in json pointer, no leading zero or sign.
+pub(crate) fn pointer_index(token: &str) -> Option<usize> {
+    if token.is_empty() || !token.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Parse path string to steps, the `Loose` syntax is not supported here.
+pub(crate) fn parse_path(p: &str, syntax: PathSyntax) -> Result<Vec<PathStep>, PathSyntaxError> {
+    let err = |pos: usize, msg: &str| PathSyntaxError { syntax, pos, msg: msg.to_string() };
+    match syntax {
+        PathSyntax::Loose => Err(err(0, "loose syntax has no steps")),
+        PathSyntax::Pointer => parse_pointer(p).map_err(|(pos, msg)| err(pos, msg)),
+        PathSyntax::Dotted => parse_dotted(p, false).map_err(|(pos, msg)| err(pos, msg)),
+        PathSyntax::Bracket => parse_dotted(p, true).map_err(|(pos, msg)| err(pos, msg)),
+    }
+}
+
+type ParseResult = Result<Vec<PathStep>, (usize, &'static str)>;
+
+fn parse_pointer(p: &str) -> ParseResult {
+    if p.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !p.starts_with('/') {
+        return Err((0, "json pointer must begin with `/`"));
+    }
+
+    let mut steps = Vec::new();
+    let mut token = String::new();
+    let mut chars = p.char_indices().skip(1);
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '/' => steps.push(PathStep::Token(std::mem::take(&mut token))),
+            '~' => match chars.next() {
+                Some((_, '0')) => token.push('~'),
+                Some((_, '1')) => token.push('/'),
+                _ => return Err((pos, "`~` must be followed by `0` or `1`")),
+            },
+            _ => token.push(c),
+        }
+    }
+    steps.push(PathStep::Token(token));
+    Ok(steps)
+}
+
+fn parse_dotted(p: &str, quoted: bool) -> ParseResult {
+    if p.is_empty() {
+        return Ok(Vec::new());
+    }
+    let bytes = p.as_bytes();
+    let mut steps = Vec::new();
+    let mut pos = 0;
+    // expect a key name at the begin or after dot
+    let mut want_name = !p.starts_with('[');
+    while pos < bytes.len() || want_name {
+        if want_name {
+            let end = p[pos..].find(['.', '[', ']']).map_or(p.len(), |i| pos + i);
+            if end == pos {
+                return Err((pos, "expect key name"));
+            }
+            steps.push(PathStep::Key(p[pos..end].to_string()));
+            pos = end;
+            want_name = false;
+            continue;
+        }
+        match bytes[pos] {
+            b'.' => {
+                pos += 1;
+                want_name = true;
+            }
+            b'[' => {
+                pos += 1;
+                let (step, next) = if quoted && matches!(bytes.get(pos), Some(b'"') | Some(b'\'')) {
+                    parse_quoted(p, pos)?
+                }
+                else {
+                    parse_index(p, pos)?
+                };
+                if bytes.get(next) != Some(&b']') {
+                    return Err((next, "expect `]`"));
+                }
+                steps.push(step);
+                pos = next + 1;
+            }
+            _ => return Err((pos, "expect `.` or `[`")),
+        }
+    }
+    Ok(steps)
+}
+
+/// Parse integer index from `pos`, return the step and the end position.
+fn parse_index(p: &str, pos: usize) -> Result<(PathStep, usize), (usize, &'static str)> {
+    let end = p[pos..].find(']').map_or(p.len(), |i| pos + i);
+    match p[pos..end].parse::<isize>() {
+        Ok(i) => Ok((PathStep::Index(i), end)),
+        Err(_) => Err((pos, "expect integer index in brackets")),
+    }
+}
+
+/// Parse quoted key begin at `pos`, return the step and the position after quote.
+fn parse_quoted(p: &str, pos: usize) -> Result<(PathStep, usize), (usize, &'static str)> {
+    let quote = p.as_bytes()[pos] as char;
+    let mut key = String::new();
+    let mut chars = p[pos + 1..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Ok((PathStep::Key(key), pos + 1 + i + 1));
+        }
+        if c == '\\' {
+            match chars.next() {
+                Some((_, e)) if e == quote || e == '\\' => key.push(e),
+                _ => return Err((pos + 1 + i, "invalid escape in quoted key")),
+            }
+            continue;
+        }
+        key.push(c);
+    }
+    Err((pos, "unclosed quoted key"))
+}
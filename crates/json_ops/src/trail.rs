@@ -0,0 +1,76 @@
+//! Opt-in record of how each node is reached while navigating by `/`, so
+//! the pointer can tell its path from root without searching the tree.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use crate::adopter::*;
+use crate::pointer::Token;
+
+/// Record of the nodes passed by `/` chain, each with its parent and the
+/// actual key or index from the parent. Pass it to `ValuePtr::with_trail()`
+/// to enable `location()`, `cursor()`, `relative()` and `matched_key()`,
+/// which then walk up the record in time of the depth.
+///
+/// Nodes are identified by address, so use one trail for one tree.
+///
+/// ```rust
+/// # use serde_json::json;
+/// # use json_ops::{ValuePath, PathTrail};
+/// let v = json!({"service": [{"name": "serv_1"}, {"name": "serv_2"}]});
+/// assert_eq!((v.path() / "service" / 1).location(), None);
+/// let trail = PathTrail::new();
+/// let root = v.path().with_trail(&trail);
+/// assert_eq!((root / "service" / -1 / "name").location(), Some("/service/1/name".to_string()));
+/// ```
+#[derive(Default)]
+pub struct PathTrail {
+    links: RefCell<HashMap<usize, (usize, Token)>>,
+}
+
+fn addr<Value>(node: &Value) -> usize {
+    node as *const Value as usize
+}
+
+impl PathTrail {
+    /// Create empty trail.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `child` is reached from `parent`, if it is a direct child.
+    pub(crate) fn link<Value>(&self, parent: &Value, child: &Value)
+    where Value: ValuePath
+    {
+        let (from, to) = (addr(parent), addr(child));
+        if self.links.borrow().get(&to).is_some_and(|(p, _)| *p == from) {
+            return;
+        }
+        let found = parent.get_children().into_iter().find(|(_, v)| std::ptr::eq(*v, child));
+        if let Some((key, _)) = found {
+            let token = match key {
+                ChildKey::Index(i) => Token::Index(i),
+                ChildKey::Key(k) => Token::Key(k.to_string()),
+            };
+            self.links.borrow_mut().insert(to, (from, token));
+        }
+    }
+
+    /// The recorded tokens from `root` down to `node`, or `None` if `node`
+    /// is not reached from `root`.
+    pub(crate) fn tokens<Value>(&self, root: &Value, node: &Value) -> Option<Vec<Token>> {
+        let links = self.links.borrow();
+        let (root, mut at) = (addr(root), addr(node));
+        let mut tokens = Vec::new();
+        while at != root {
+            // stale record of dropped tree may form a loop
+            if tokens.len() >= links.len() {
+                return None;
+            }
+            let (parent, token) = links.get(&at)?;
+            tokens.push(token.clone());
+            at = *parent;
+        }
+        tokens.reverse();
+        Some(tokens)
+    }
+}
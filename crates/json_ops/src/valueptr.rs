@@ -1,15 +1,42 @@
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
 use crate::adopter::*;
+use crate::pointer::*;
+use crate::patherr::*;
+use crate::syntax::*;
+use crate::cursor::ValueCursor;
+use crate::relative::*;
+use crate::matcher::{KeyMatcher, Glob};
+use crate::descend::Descendants;
+use crate::embed::*;
+use crate::trail::PathTrail;
 
 /// Wrap `Option<&Value>` as pointer to json node for operator overload.
 ///
 /// It can used as `Option` implicitly at most time, as overload `*` Deref trait,
 /// where `None` means refer to non-exist node, and `'tr` lifetime refers to 
 /// the overall json tree.
-#[derive(Eq, PartialEq, Debug)]
+///
+/// It also keeps the root node where the `/` chain begins, so can explain
+/// where it failed by `error()` when it is `None`, and tell the path where
+/// it points to by `location()` if the chain is recorded by `with_trail()`.
 pub struct ValuePtr<'tr, Value>
 where Value: ValuePath + ValueReader
 {
     ptr: Option<&'tr Value>,
+    /// The root where `/` chain begins, or the parent node where it failed
+    /// if `ptr` is `None`, to keep the pointer small.
+    root: Option<&'tr Value>,
+    /// Count of path segments resolved, or the index of failed segment.
+    depth: u32,
+    /// The failed token if `ptr` is `None`.
+    token: TokenBuf,
+    /// How to match key in path.
+    matcher: KeyMatcher,
+    /// Where to parse json text in string node, if navigate into it.
+    embed: Option<&'tr EmbedCache<Value>>,
+    /// Where to record the nodes passed, if track the path.
+    trail: Option<&'tr PathTrail>,
 }
 
 // atuo dervie(Copy, Clone) failed as `Value: Copy` may not satisfied.
@@ -35,11 +62,290 @@ where Value: ValuePath + ValueReader
 ///
 /// Note that mutable reference don't support copy, only use it when you really 
 /// need to modify the pointed json node, otherwise use the immutable pointer.
-#[derive(Eq, PartialEq, Debug)]
+///
+/// It records the resolved path tokens while moving by `/` or writing by `<<`,
+/// so can tell the path where it points to by `location()`.
 pub struct ValuePtrMut<'tr, Value>
 where Value: ValuePath + ValueReader + ValueWriter
 {
     ptr: Option<&'tr mut Value>,
+    /// The array to append when resolved by `-` token, while `ptr` is `None`.
+    slot: Option<&'tr mut Value>,
+    /// The resolved path tokens from where the `/` chain begins,
+    /// `None` if unknown, such as taken out from a pointer set.
+    trail: Option<Vec<Token>>,
+    /// How to match key in path.
+    matcher: KeyMatcher,
+    /// Where to log string node expanded in place, if navigate into it.
+    embed: Option<&'tr EmbedLog<Value>>,
+    /// Create missing nodes while navigating, in entry mode.
+    create: bool,
+    /// The pointed node is just created in entry mode, blank to be any kind.
+    fresh: bool,
+    /// Why entry mode failed to create the node, kept along the `/` chain.
+    refused: Option<EntryError>,
+}
+
+// Only compare and debug the pointed node, as the `Option<&Value>` it wraps.
+impl<'tr, Value> PartialEq for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader + PartialEq
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl<'tr, Value> Eq for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader + Eq
+{
+}
+
+impl<'tr, Value> fmt::Debug for ValuePtr<'tr, Value>
+where Value: ValuePath + ValueReader + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValuePtr").field("ptr", &self.ptr).finish()
+    }
+}
+
+impl<'tr, Value> PartialEq for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter + PartialEq
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl<'tr, Value> Eq for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter + Eq
+{
+}
+
+impl<'tr, Value> fmt::Debug for ValuePtrMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValuePtrMut").field("ptr", &self.ptr).finish()
+    }
+}
+
+/// Set of json pointers to many nodes, yield from wildcard path token.
+///
+/// It is `Vec<&Value>` implicitly as overload `*` Deref trait, and can
+/// further chain operator `/` to each node, dropping the ones not exist.
+/// String token `"*"` is always literal key, use `Wildcard` to expand again.
+pub struct ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    ptrs: Vec<&'tr Value>,
+    root: Option<&'tr Value>,
+    trail: Option<&'tr PathTrail>,
+    matcher: KeyMatcher,
+    embed: Option<&'tr EmbedCache<Value>>,
+}
+
+/// Set of mutable json pointers, yield from range slice of array.
+///
+/// It is `Vec<&mut Value>` implicitly as overload `*` Deref trait,
+/// and can iterate each one as `ValuePtrMut`.
+#[derive(Eq, PartialEq, Debug)]
+pub struct ValuePtrSetMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    ptrs: Vec<&'tr mut Value>,
+}
+
+impl<'tr, Value> Clone for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    fn clone(&self) -> Self {
+        self.renew(self.ptrs.clone())
+    }
+}
+
+impl<'tr, Value> PartialEq for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader + PartialEq
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ptrs == other.ptrs
+    }
+}
+
+impl<'tr, Value> Eq for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader + Eq
+{
+}
+
+impl<'tr, Value> fmt::Debug for ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValuePtrSet").field("ptrs", &self.ptrs).finish()
+    }
+}
+
+/// Wildcard path token to select all child nodes of array or object.
+/// Used in operator `/` to turn `ValuePtr` into `ValuePtrSet`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Wildcard;
+
+/// Convert range to `start..end` of array with `len` items, where `len` is
+/// only used when the end is unbounded.
+fn range_bounds<R: RangeBounds<usize>>(range: &R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(s) => *s,
+        Bound::Excluded(s) => s.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(e) => e.saturating_add(1),
+        Bound::Excluded(e) => *e,
+        Bound::Unbounded => len,
+    };
+    (start, end)
+}
+
+/// Children of object node whose key matches.
+fn matched_children<Value, F>(node: &Value, pred: F) -> Vec<&Value>
+where Value: ValuePath, F: Fn(&str) -> bool
+{
+    node.get_keys().into_iter()
+        .filter(|k| pred(k))
+        .filter_map(|k| node.get_key(k))
+        .collect()
+}
+
+/// Parse path token as array index, may be negative.
+fn token_index(token: &str, len: usize) -> Option<usize> {
+    token.parse::<isize>().ok().and_then(|i| i.index_in(len))
+}
+
+/// Node that can be array in entry mode: array, null, or empty object that
+/// is just created in entry mode. Existing object is never turned into array.
+fn is_indexable<Value>(node: &Value, fresh: bool) -> bool
+where Value: ValuePath + ValueReader
+{
+    match node.get_kind() {
+        NodeKind::Array | NodeKind::Null => true,
+        NodeKind::Object => fresh && node.get_keys().is_empty(),
+        _ => false,
+    }
+}
+
+/// Get or insert member by key in entry mode.
+fn entry_member<'a, Value>(node: &'a mut Value, key: &str) -> Result<&'a mut Value, EntryError>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    let found = node.get_kind();
+    node.entry_key(key).ok_or_else(|| EntryError::Mismatch { token: key.to_string(), found })
+}
+
+/// Get or pad item by index in entry mode, if the node is indexable.
+fn entry_item<Value>(node: &mut Value, i: usize, fresh: bool) -> Result<&mut Value, EntryError>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    let (found, len) = (node.get_kind(), node.get_len());
+    if !is_indexable(node, fresh) {
+        return Err(EntryError::Mismatch { token: i.to_string(), found });
+    }
+    if i.checked_sub(len).is_some_and(|pad| pad >= ENTRY_PAD_LIMIT) {
+        return Err(EntryError::TooFar { index: i, len, limit: ENTRY_PAD_LIMIT });
+    }
+    node.entry_index(i).ok_or_else(|| EntryError::Mismatch { token: i.to_string(), found })
+}
+
+/// Get or create child by token in entry mode, as array item if token is
+/// index and node is indexable, otherwise as object member.
+fn entry<'a, Value>(node: &'a mut Value, token: &str, fresh: bool) -> Result<(&'a mut Value, Token), EntryError>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    match token.parse::<usize>() {
+        Ok(i) if is_indexable(node, fresh) => Ok((entry_item(node, i, fresh)?, Token::Index(i))),
+        _ => Ok((entry_member(node, token)?, Token::Key(token.to_string()))),
+    }
+}
+
+/// Get or create child by parsed step in entry mode, the same as `entry()`
+/// but key or index only as the step says.
+fn entry_step<'a, Value>(node: &'a mut Value, step: &PathStep, fresh: bool) -> Result<(&'a mut Value, Token), EntryError>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    match step {
+        PathStep::Key(key) => Ok((entry_member(node, key)?, Token::Key(key.clone()))),
+        PathStep::Index(i) => match usize::try_from(*i) {
+            Ok(i) => Ok((entry_item(node, i, fresh)?, Token::Index(i))),
+            Err(_) => Err(EntryError::Mismatch { token: i.to_string(), found: node.get_kind() }),
+        }
+        PathStep::Token(token) => match pointer_index(token) {
+            Some(i) if is_indexable(node, fresh) => Ok((entry_item(node, i, fresh)?, Token::Index(i))),
+            _ => Ok((entry_member(node, token)?, Token::Key(token.clone()))),
+        }
+    }
+}
+
+/// Serialize back the expanded nodes under `v` before change its children,
+/// so that no logged path turns stale. Only works when the path is tracked.
+fn settle_embedded<Value>(embed: Option<&EmbedLog<Value>>, trail: Option<&[Token]>, v: &mut Value)
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    if let (Some(log), Some(trail)) = (embed, trail) {
+        log.settle(v, trail, false);
+    }
+}
+
+/// Path operand joined by more than one token, leading `/` is optional.
+fn is_joined(p: &str) -> bool {
+    split_loose(p).nth(1).is_some()
+}
+
+/// Parse predicate token as `key[field=value]`, where key may be empty to
+/// select item from current array, and value may be quoted to match string.
+fn parse_predicate(token: &str) -> Option<(&str, &str, &str)> {
+    let (key, cond) = token.strip_suffix(']')?.split_once('[')?;
+    let (field, value) = cond.split_once('=')?;
+    if field.is_empty() {
+        return None;
+    }
+    Some((key, field, value))
+}
+
+/// Check if scalar node equals the value text in predicate.
+fn scalar_is(scalar: Scalar<'_>, text: &str) -> bool {
+    for quote in ['"', '\''] {
+        if let Some(quoted) = text.strip_prefix(quote).and_then(|t| t.strip_suffix(quote)) {
+            return scalar == Scalar::Str(quoted);
+        }
+    }
+    match scalar {
+        Scalar::Str(s) => s == text,
+        Scalar::Int(i) => text.parse::<i64>() == Ok(i),
+        Scalar::Float(f) => text.parse::<f64>() == Ok(f),
+        Scalar::Bool(b) => text.parse::<bool>() == Ok(b),
+        Scalar::Null => text == "null",
+    }
+}
+
+/// Resolve predicate token from node, return the actual key of array if
+/// any, and the index of the first item whose field equals the value.
+pub(crate) fn hop_predicate<Value>(node: &Value, token: &str, matcher: KeyMatcher) -> Option<(Option<String>, usize)>
+where Value: ValuePath + ValueReader
+{
+    let (key, field, text) = parse_predicate(token)?;
+    let (key, array) = if key.is_empty() {
+        (None, node)
+    }
+    else {
+        let key = matcher.actual_key(node, key)?;
+        let array = node.get_key(&key)?;
+        (Some(key), array)
+    };
+    let index = (0..array.get_len()).find(|i| {
+        array.get_index(*i)
+            .and_then(|item| matcher.get(item, field))
+            .and_then(|v| v.get_scalar())
+            .is_some_and(|s| scalar_is(s, text))
+    })?;
+    Some((key, index))
 }
 
 /// Proxy `get_*` methods of `Value` for json pointer.
@@ -62,15 +368,240 @@ where Value: ValuePath + ValueReader
     /// Usually there is no need to create `ValuePtr` instance directly, but yield one
     /// from existed json `Value`, except `None`.
     pub fn new(ptr: Option<&'tr Value>) -> Self {
-        Self { ptr }
+        Self { ptr, root: ptr, depth: 0, token: TokenBuf::empty(), matcher: KeyMatcher::Exact, embed: None, trail: None }
+    }
+
+    /// Navigate into json text in string node for the following `/` chain,
+    /// parse it once and keep the tree in `cache`. The node inside is not
+    /// in the tree of root, so its `location()` is `None`.
+    pub fn with_embedded(self, cache: &'tr EmbedCache<Value>) -> Self {
+        Self { embed: Some(cache), ..self }
+    }
+
+    /// Record the nodes passed by the following `/` chain in `trail`, to tell
+    /// the path from root by `location()` and move up by `cursor()`.
+    pub fn with_trail(self, trail: &'tr PathTrail) -> Self {
+        Self { trail: Some(trail), ..self }
+    }
+
+    /// Record in trail that `child` is reached from `parent`, if tracking.
+    fn track(&self, parent: &'tr Value, child: &'tr Value) -> &'tr Value {
+        if let Some(trail) = self.trail {
+            trail.link(parent, child);
+        }
+        child
     }
 
-    /// Resolve to sub path, by single index.
+    /// The recorded path tokens from root, if tracking.
+    fn tokens(&self) -> Option<Vec<Token>> {
+        self.trail?.tokens(self.root?, self.ptr?)
+    }
+
+    /// Open json text in string node as tree if navigate into it.
+    fn open(&self, v: &'tr Value) -> &'tr Value {
+        match self.embed {
+            Some(cache) if v.get_kind() == NodeKind::String => cache.open(v).unwrap_or(v),
+            _ => v,
+        }
+    }
+
+    /// Set the policy to match key for the following `/` chain.
+    pub fn with_matcher(self, matcher: KeyMatcher) -> Self {
+        Self { matcher, ..self }
+    }
+
+    /// Fallback to `other` pointer if this one points to nothing, can chain
+    /// many and then read with `| default`. Also as operator `|` between
+    /// two pointers.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::ValuePath;
+    /// let v = json!({"host": {"port": 80}, "ip": "127.0.0.1"});
+    /// assert_eq!((v.path()/"host"/"ip").or(v.path()/"ip") | "0.0.0.0", "127.0.0.1");
+    /// assert_eq!((v.path()/"host"/"ip").or(v.path()/"addr") | "0.0.0.0", "0.0.0.0");
+    /// assert_eq!((v.path()/"host"/"port").or(v.path()/"port") | 0, 80);
+    /// ```
+    pub fn or(self, other: Self) -> Self {
+        if self.ptr.is_some() { self } else { other }
+    }
+
+    /// The actual key in parent object of the pointed node, which may differ
+    /// from the key in path if match loosely by `KeyMatcher`.
+    /// Return `None` if it points to nothing or not a child of object,
+    /// or the path is not tracked by `with_trail()`.
+    pub fn matched_key(&self) -> Option<&'tr str> {
+        self.cursor()?.key()
+    }
+
+    /// Point to node `v` of a pointer set, keep the root if any.
+    fn place(self, v: &'tr Value) -> Self {
+        Self { ptr: Some(v), root: self.root.or(Some(v)), ..self }
+    }
+
+    /// Move down to `next` node in the same tree by `n` segments, keep the root.
+    fn step(&self, next: &'tr Value, n: u32) -> Self {
+        Self { ptr: Some(next), depth: self.depth + n, ..*self }
+    }
+
+    /// Fail to resolve `token` from `parent` node, `n` segments after current,
+    /// which is kept in place of the root.
+    fn fail(&self, parent: &'tr Value, n: u32, token: &str) -> Self {
+        Self { ptr: None, root: Some(parent), depth: self.depth + n, token: TokenBuf::new(parent, token), ..*self }
+    }
+
+    /// Explain why the path resolved to `None`: which segment and token failed,
+    /// the reason and the kind of node found there, with near-miss keys.
+    /// Return `None` if it points to some node, or created by `new(None)`.
+    ///
+    /// Only the failed node and token is recorded while moving by `/`,
+    /// the error detail is built when this method called.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::{ValuePath, PathErrorKind};
+    /// let v = json!({"host": {"port": 8080}});
+    /// let err = (v.path() / "host" / "prot").error().unwrap();
+    /// assert_eq!(err.segment, 1);
+    /// assert_eq!(err.kind, PathErrorKind::MissingKey);
+    /// assert_eq!(err.suggestions, vec!["port"]);
+    /// assert_eq!(err.to_string(), "segment 1 `prot`: key not found at object node, did you mean `port`?");
+    /// ```
+    pub fn error(&self) -> Option<PathError> {
+        match (self.ptr, self.root) {
+            (None, Some(parent)) => Some(explain(parent, self.depth, &self.token)),
+            _ => None,
+        }
+    }
+
+    /// Get the path where it points to from the root where `/` chain begins,
+    /// as canonical json pointer string such as `/service/1/desc`.
+    /// Return `None` if it points to nothing, or the path is not tracked.
+    ///
+    /// The path is tracked only if the chain begins with `with_trail()`, that
+    /// records the actual key or index of each node passed, to keep the
+    /// pointer small and cheap to copy. Use `find_paths()` to search a node
+    /// from root if not tracked.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::{ValuePath, PathTrail};
+    /// let v = json!({"service": [{"name": "serv_1"}, {"name": "serv_2"}]});
+    /// let trail = PathTrail::new();
+    /// let node = v.path().with_trail(&trail).pathto("service[name=serv_2]");
+    /// assert_eq!(node.location(), Some("/service/1".to_string()));
+    /// ```
+    pub fn location(&self) -> Option<String> {
+        Some(join_pointer(&self.tokens()?))
+    }
+
+    /// Convert to navigable cursor with ancestors from the root where `/` chain
+    /// begins, so can move to parent or sibling.
+    /// Return `None` if it points to nothing, or the path is not tracked as
+    /// `location()`.
+    pub fn cursor(&self) -> Option<ValueCursor<'tr, Value>> {
+        self.tokens()?.iter().try_fold(ValueCursor::new(self.root?), |cur, token| match token {
+            Token::Key(key) => cur.child(key),
+            Token::Index(i) => cur.item(*i),
+        })
+    }
+
+    /// Search all descendants with the key `name` in the whole subtree,
+    /// depth-first, yielding each with its json pointer path from the root
+    /// where `/` chain begins. The path of current node is got by `location()`
+    /// once, or empty if not tracked, then the path is from current node.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::{ValuePath, PathTrail};
+    /// let v = json!({"service": [{"name": "serv_1"}, {"name": "serv_2", "host": {"name": "h"}}]});
+    /// let names: Vec<_> = (v.path() / "service").descendants_named("name").collect();
+    /// assert_eq!(names[0], ("/0/name".to_string(), &json!("serv_1")));
+    /// let trail = PathTrail::new();
+    /// let names: Vec<_> = (v.path().with_trail(&trail) / "service").descendants_named("name").collect();
+    /// assert_eq!(names[1].0, "/service/1/host/name");
+    /// ```
+    pub fn descendants_named<'a>(&self, name: &'a str)
+        -> Descendants<'tr, Value, impl FnMut(ChildKey<'_>, &str, &Value) -> bool + 'a>
+    {
+        Descendants::new(self.ptr, self.location().unwrap_or_default(),
+            move |step, _, _| step == ChildKey::Key(name))
+    }
+
+    /// Search all descendants that `pred(path, node)` returns true, where the
+    /// path is json pointer from the root where `/` chain begins, the same as
+    /// `descendants_named()`.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::ValuePath;
+    /// let v = json!({"a": {"port": 80}, "b": [8080, "x"]});
+    /// let ports: Vec<_> = v.path().descendants_where(|_, node| node.is_u64())
+    ///     .map(|(path, _)| path).collect();
+    /// assert_eq!(ports, vec!["/a/port", "/b/0"]);
+    /// ```
+    pub fn descendants_where<F>(&self, mut pred: F)
+        -> Descendants<'tr, Value, impl FnMut(ChildKey<'_>, &str, &Value) -> bool>
+    where F: FnMut(&str, &Value) -> bool
+    {
+        Descendants::new(self.ptr, self.location().unwrap_or_default(),
+            move |_, path, node| pred(path, node))
+    }
+
+    /// Evaluate Relative JSON Pointer from current position, such as `1/name`
+    /// to get sibling field, or `0#` to get the key or index of current node.
+    /// It can move up to the root where the `/` chain begins, by `cursor()`
+    /// to know its ancestors, so the path should be tracked by `with_trail()`.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::{ValuePath, Relative, PathTrail};
+    /// let v = json!({"service": [{"name": "serv_1"}, {"name": "serv_2", "port": 80}]});
+    /// let trail = PathTrail::new();
+    /// let port = v.path().with_trail(&trail) / "service" / 1 / "port";
+    /// assert_eq!(port.relative("1/name").unwrap().ptr().unwrap() | "", "serv_2");
+    /// assert_eq!(port.relative("1-1/name").unwrap().ptr().unwrap() | "", "serv_1");
+    /// assert_eq!(port.relative("0#").unwrap(), Relative::Key("port".to_string()));
+    /// assert_eq!(port.relative("1#").unwrap(), Relative::Index(1));
+    /// ```
+    pub fn relative(&self, rel: &str) -> Result<Relative<Self>, RelativeError> {
+        let rel = parse_relative(rel)?;
+        let mut cur = self.cursor().ok_or(RelativeError::NoNode)?;
+        for _ in 0..rel.up {
+            cur = cur.parent().ok_or(RelativeError::AboveRoot)?;
+        }
+        if rel.shift != 0 {
+            let i = cur.index().ok_or(RelativeError::NotArrayItem)?;
+            let parent = cur.parent().ok_or(RelativeError::NotArrayItem)?;
+            cur = i.checked_add_signed(rel.shift)
+                .and_then(|i| parent.item(i))
+                .ok_or(RelativeError::IndexOutOfRange)?;
+            self.track(parent.value(), cur.value());
+        }
+
+        match rel.tail {
+            Tail::Name => match (cur.key(), cur.index()) {
+                (Some(key), _) => Ok(Relative::Key(key.to_string())),
+                (_, Some(i)) => Ok(Relative::Index(i)),
+                _ => Err(RelativeError::NoName),
+            },
+            Tail::Steps(steps) => {
+                let origin = Self { ptr: Some(cur.value()), depth: cur.depth() as u32, ..*self };
+                Ok(Relative::Ptr(origin.path_steps(&steps)))
+            }
+        }
+    }
+
+    /// Resolve to sub path, by single index which count from end if negative.
     /// Used in operator `/`.
-    fn path_index(&self, i: usize) -> Self {
-        match self.ptr {
-            Some(v) => Self::new(v.get_index(i)),
-            None => Self::new(None)
+    fn path_index<I: PathIndex>(&self, i: I) -> Self {
+        let v = match self.ptr {
+            Some(v) => self.open(v),
+            None => return *self,
+        };
+        match i.index_in(v.get_len()).and_then(|i| v.get_index(i)) {
+            Some(next) => self.step(self.track(v, next), 1),
+            None => self.fail(v, 0, &i.to_string()),
         }
     }
 
@@ -78,39 +609,182 @@ where Value: ValuePath + ValueReader
     /// Used in operator `/`.
     fn path_str(&self, p: &str) -> Self {
         if self.ptr.is_none() {
-            return Self::new(None);
+            return *self;
         }
 
-        let v = self.ptr.unwrap();
-        let target = v.get_key(p);
-        if target.is_some() {
-            Self::new(target)
+        let v = self.open(self.ptr.unwrap());
+        if let Some(target) = self.matcher.get(v, p) {
+            self.step(self.track(v, target), 1)
         }
         else {
             self.pathto(p)
         }
     }
 
+    /// Resolve to items of array in range.
+    /// Used in operator `/`.
+    fn path_range<R: RangeBounds<usize>>(&self, range: R) -> ValuePtrSet<'tr, Value> {
+        match self.ptr {
+            Some(v) => {
+                let v = self.open(v);
+                let (start, end) = range_bounds(&range, v.get_len());
+                self.spread(v, v.get_slice(start, end))
+            }
+            None => self.spread_none()
+        }
+    }
+
+    /// Resolve to all children, by wildcard.
+    /// Used in operator `/`.
+    fn path_any(&self) -> ValuePtrSet<'tr, Value> {
+        match self.ptr {
+            Some(v) => {
+                let v = self.open(v);
+                self.spread(v, children(v))
+            }
+            None => self.spread_none()
+        }
+    }
+
+    /// Resolve to all children whose key matches, by glob or regex.
+    /// Used in operator `/`.
+    fn path_keys<F: Fn(&str) -> bool>(&self, pred: F) -> ValuePtrSet<'tr, Value> {
+        match self.ptr {
+            Some(v) => {
+                let v = self.open(v);
+                self.spread(v, matched_children(v, pred))
+            }
+            None => self.spread_none()
+        }
+    }
+
+    /// Pointer set to `items` of node `v`, in the same chain.
+    fn spread(&self, v: &'tr Value, items: Vec<&'tr Value>) -> ValuePtrSet<'tr, Value> {
+        items.iter().for_each(|item| { self.track(v, item); });
+        ValuePtrSet::in_tree(items, self)
+    }
+
+    /// Empty pointer set in the same chain.
+    fn spread_none(&self) -> ValuePtrSet<'tr, Value> {
+        ValuePtrSet::in_tree(Vec::new(), self)
+    }
+
+    /// Get item of array selected by predicate, and track it.
+    fn hop_item(&self, array: &'tr Value, index: usize) -> Option<&'tr Value> {
+        Some(self.track(array, array.get_index(index)?))
+    }
+
     /// Resolve to sub path by json pointer syntax but leading '/' is optional.
+    /// Negative index token count from end of array, as `array/-1` is the last one.
+    /// Token as `key[field=value]` selects the first item of array `key`
+    /// whose `field` equals `value`.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::ValuePath;
+    /// let v = json!({"service": [{"name": "serv_1", "port": 80}, {"name": "serv_2", "port": 8080}]});
+    /// assert_eq!(v.pathto("service[name=serv_2]/port") | 0, 8080);
+    /// assert_eq!(v.pathto("service[port=80].name") | "", "serv_1");
+    /// assert_eq!(v.path() / "service" / "[name=\"serv_1\"]" / "port" | 0, 80);
+    /// ```
     pub fn pathto(&self, p: &str) -> Self {
         if self.ptr.is_none() {
-            return Self::new(None);
+            return *self;
+        }
+
+        let mut value = self.ptr.unwrap();
+        let mut n = 0;
+        for token in split_loose(p) {
+            value = self.open(value);
+            let next = self.matcher.get(value, &token).or_else(||
+                token_index(&token, value.get_len()).and_then(|x| value.get_index(x))
+                ).or_else(|| {
+                    let (key, index) = hop_predicate(value, &token, self.matcher)?;
+                    match key {
+                        Some(key) => self.hop_item(self.track(value, value.get_key(&key)?), index),
+                        None => self.hop_item(value, index),
+                    }
+                });
+            match next {
+                Some(next) => value = self.track(value, next),
+                None => return self.fail(value, n, &token),
+            }
+            n += 1;
         }
 
-        let mut fixp = p;
-        if !p.is_empty() && p.chars().nth(0) == Some('/') {
-            fixp = &p[1..];
+        self.step(value, n)
+    }
+
+    /// Resolve to sub path by precompiled pointer.
+    /// Used in operator `/`.
+    fn path_pointer(&self, p: &Pointer) -> Self {
+        let mut value = match self.ptr {
+            Some(v) => v,
+            None => return *self,
+        };
+
+        for n in 0..p.len() {
+            let (key, index) = p.step(n);
+            value = self.open(value);
+            let next = self.matcher.get(value, key).or_else(||
+                index.and_then(|i| i.index_in(value.get_len())).and_then(|x| value.get_index(x))
+                ).or_else(|| {
+                    let (key, index) = hop_predicate(value, key, self.matcher)?;
+                    match key {
+                        Some(key) => self.hop_item(self.track(value, value.get_key(&key)?), index),
+                        None => self.hop_item(value, index),
+                    }
+                });
+            match next {
+                Some(next) => value = self.track(value, next),
+                None => return self.fail(value, n as u32, key),
+            }
         }
 
-        let target = fixp.split(&['/', '.'][..])
-            .map(|x| x.replace("~1", "/").replace("~0", "~"))
-            .try_fold(self.ptr.unwrap(), |value, token| {
-                value.get_key(&token).or_else(||
-                    token.parse::<usize>().ok().and_then(|x| value.get_index(x))
-                    )
-            });
+        self.step(value, p.len() as u32)
+    }
 
-        Self::new(target)
+    /// Resolve to sub path in the specified syntax, `Loose` is the same as
+    /// `pathto()`, while other syntax may fail to parse the path string.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::{ValuePath, PathSyntax};
+    /// let v = json!({"v1.2": {"list": [1, 2]}});
+    /// let node = v.path().pathto_with(r#"["v1.2"].list[-1]"#, PathSyntax::Bracket).unwrap();
+    /// assert_eq!(node | 0, 2);
+    /// assert!(v.path().pathto_with("v1.2", PathSyntax::Dotted).unwrap().is_none());
+    /// assert!(v.path().pathto_with("v1.2", PathSyntax::Pointer).is_err());
+    /// ```
+    pub fn pathto_with(&self, p: &str, syntax: PathSyntax) -> Result<Self, PathSyntaxError> {
+        if syntax == PathSyntax::Loose {
+            return Ok(self.pathto(p));
+        }
+        let steps = parse_path(p, syntax)?;
+        Ok(self.path_steps(&steps))
+    }
+
+    /// Resolve to sub path by parsed steps.
+    fn path_steps(&self, steps: &[PathStep]) -> Self {
+        let mut value = match self.ptr {
+            Some(v) => v,
+            None => return *self,
+        };
+
+        for (n, step) in steps.iter().enumerate() {
+            value = self.open(value);
+            let next = match hop(value, step, self.matcher) {
+                Some(Hop::Key(key)) => value.get_key(&key),
+                Some(Hop::Index(i)) => value.get_index(i),
+                None => None,
+            };
+            match next {
+                Some(next) => value = self.track(value, next),
+                None => return self.fail(value, n as u32, &step.token()),
+            }
+        }
+
+        self.step(value, steps.len() as u32)
     }
 
     /// Get a str ref if the value type matches, or defalut `rhs`.
@@ -136,7 +810,266 @@ where Value: ValuePath + ValueReader + ValueWriter
     /// Usually there is no need to create `ValuePtr` instance directly, but yield one
     /// from existed json `Value`, except `None`.
     pub fn new(ptr: Option<&'tr mut Value>) -> Self {
-        Self { ptr }
+        Self { ptr, slot: None, trail: Some(Vec::new()), matcher: KeyMatcher::Exact, embed: None, create: false, fresh: false, refused: None }
+    }
+
+    /// Switch to entry mode for the following `/` chain, that creates missing
+    /// nodes like `mkdir -p`: missing key is inserted as blank node, which is
+    /// turned into array if followed by index, or object if followed by key.
+    /// Index beyond the end of array is padded with blank nodes. The blank
+    /// node is `null` for json, or empty table for toml. Existing object is
+    /// never turned into array, even if empty.
+    /// A missing key after `/` is created as literal key, even if containing
+    /// `/` or `.`, unless the joined path exists already; while `pathto()`
+    /// splits the path and creates each token. Path by `Pointer` or by
+    /// `pathto_with()` in other syntax also creates missing nodes.
+    /// It fails only if the node in the middle is scalar or mismatch type,
+    /// or the index is too far beyond the end of array, then `entry_error()`
+    /// tells why. The nodes created before the failed token are left in place.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::{ValuePath, EntryError};
+    /// let mut v = json!({});
+    /// let _ = v.path_mut().entry() / "a" / "b" / 1 / "c" << 3;
+    /// assert_eq!(v, json!({"a": {"b": [null, {"c": 3}]}}));
+    /// let _ = v.path_create().pathto("a/d") << "x";
+    /// assert_eq!(v["a"]["d"], "x");
+    /// assert!((v.path_create() / "a" / "d" / "e").is_none());
+    ///
+    /// let mut v = json!(null);
+    /// let node = v.path_create() / "a" / 5000;
+    /// assert_eq!(node.entry_error(), Some(&EntryError::TooFar { index: 5000, len: 0, limit: 1024 }));
+    /// assert_eq!(v, json!({"a": null}));
+    /// ```
+    pub fn entry(mut self) -> Self {
+        self.create = true;
+        self
+    }
+
+    /// Tell why entry mode failed to create the missing node, `None` if it
+    /// did not fail in entry mode.
+    pub fn entry_error(&self) -> Option<&EntryError> {
+        self.refused.as_ref()
+    }
+
+    /// Expand string node in place while navigating, used by `EmbeddedMut`.
+    pub(crate) fn with_embed_log(mut self, log: &'tr EmbedLog<Value>) -> Self {
+        self.embed = Some(log);
+        self
+    }
+
+    /// Expand json text in string node in place if navigate into it, and log
+    /// its path reached by `tokens` after the trail to serialize back later.
+    /// Only works when the path is tracked.
+    fn open(&self, v: &mut Value, tokens: &[Token]) {
+        if let (Some(log), Some(trail)) = (self.embed, self.trail.as_ref()) {
+            log.expand(v, trail, tokens);
+        }
+    }
+
+    /// Set the policy to match key for the following `/` chain.
+    pub fn with_matcher(mut self, matcher: KeyMatcher) -> Self {
+        self.matcher = matcher;
+        self
+    }
+
+    /// The actual key in parent object of the pointed node, which may differ
+    /// from the key in path if match loosely by `KeyMatcher`.
+    /// Return `None` if it points to nothing or not a child of object.
+    pub fn matched_key(&self) -> Option<&str> {
+        self.ptr.as_ref()?;
+        match self.trail.as_ref()?.last()? {
+            Token::Key(key) => Some(key),
+            Token::Index(_) => None,
+        }
+    }
+
+    /// Pointer to node whose path from root is unknown.
+    fn untracked(ptr: Option<&'tr mut Value>) -> Self {
+        Self { ptr, slot: None, trail: None, matcher: KeyMatcher::Exact, embed: None, create: false, fresh: false, refused: None }
+    }
+
+    /// Move to another node in the same tree, and append path tokens
+    /// to the trail if success.
+    fn step(&mut self, ptr: Option<&'tr mut Value>, tokens: &[Token]) -> Self {
+        let mut trail = self.trail.take();
+        if let (Some(trail), true) = (trail.as_mut(), ptr.is_some()) {
+            trail.extend(tokens.iter().cloned());
+        }
+        let refused = if ptr.is_none() { self.refused.take() } else { None };
+        Self { ptr, slot: None, trail, matcher: self.matcher, embed: self.embed, create: self.create, fresh: false, refused }
+    }
+
+    /// Fail to create missing node in entry mode.
+    fn refuse(&mut self, err: EntryError) -> Self {
+        let mut next = self.step(None, &[]);
+        next.refused = Some(err);
+        next
+    }
+
+    /// Pointer to the new item after the end of array, by `-` token.
+    /// It refer to non-exist node until write something by operator `<<`.
+    fn append_slot(&mut self, array: &'tr mut Value, tokens: &[Token]) -> Self {
+        let mut trail = self.trail.take();
+        if let Some(trail) = trail.as_mut() {
+            trail.extend(tokens.iter().cloned());
+        }
+        Self { ptr: None, slot: Some(array), trail, matcher: self.matcher, embed: self.embed, create: self.create, fresh: false, refused: None }
+    }
+
+    /// Get the path where it points to from the root where `/` chain begins,
+    /// as canonical json pointer string such as `/service/1/desc`.
+    /// Return `None` if it points to nothing, or taken out from pointer set
+    /// that the path is not tracked.
+    pub fn location(&self) -> Option<String> {
+        self.ptr.as_ref()?;
+        Some(join_pointer(self.trail.as_ref()?))
+    }
+
+    /// Insert item to array at index which count from end if negative, the
+    /// end of array is also valid to append. Return `false` if not inserted.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::ValuePath;
+    /// let mut v = json!({"chain": ["auth", "log"]});
+    /// let mut chain = v.path_mut() / "chain";
+    /// assert!(chain.insert_at(0, "cors"));
+    /// assert!(chain.insert_at(-1, "gzip"));
+    /// assert!(chain.insert_at(4, "proxy"));
+    /// assert!(!chain.insert_at(9, "none"));
+    /// assert_eq!(v["chain"], json!(["cors", "auth", "gzip", "log", "proxy"]));
+    /// ```
+    pub fn insert_at<I: PathIndex, T>(&mut self, i: I, val: T) -> bool
+    where Value: From<T>
+    {
+        let v = match self.ptr.as_deref_mut() {
+            Some(v) => v,
+            None => return false,
+        };
+        settle_embedded(self.embed, self.trail.as_deref(), v);
+        match i.index_in(v.get_len()) {
+            Some(index) => v.insert_array(index, val),
+            None => false,
+        }
+    }
+
+    /// Replace items of array in range by `items`, return the removed items,
+    /// or `None` if not array. The range is clamped to the array.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::ValuePath;
+    /// let mut v = json!([1, 2, 3, 4]);
+    /// assert_eq!(v.path_mut().splice(1..3, ["a", "b", "c"]), Some(vec![json!(2), json!(3)]));
+    /// assert_eq!(v.path_mut().splice(.., Vec::<i64>::new()).unwrap().len(), 5);
+    /// assert_eq!(v, json!([]));
+    /// ```
+    pub fn splice<R, T, It>(&mut self, range: R, items: It) -> Option<Vec<Value>>
+    where R: RangeBounds<usize>, It: IntoIterator<Item = T>, Value: From<T>
+    {
+        let v = self.ptr.as_deref_mut()?;
+        settle_embedded(self.embed, self.trail.as_deref(), v);
+        let (start, end) = range_bounds(&range, v.get_len());
+        v.splice_array(start, end, items.into_iter().map(Value::from).collect())
+    }
+
+    /// Remove the last item of array and return it.
+    pub fn pop(&mut self) -> Option<Value> {
+        let v = self.ptr.as_deref_mut()?;
+        settle_embedded(self.embed, self.trail.as_deref(), v);
+        v.pop_array()
+    }
+
+    /// Remove child of current node by key, or by index for array which
+    /// count from end if negative, and return the detached value.
+    /// As mutable pointer doesn't keep its parent, remove the node itself
+    /// from the parent pointer instead. Also as operator `>>`.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::ValuePath;
+    /// let mut v = json!({"host": {"ip": "127.0.0.1", "port": 80}, "list": [1, 2, 3]});
+    /// assert_eq!((v.path_mut() / "host").remove("port"), Some(json!(80)));
+    /// assert_eq!((v.path_mut() / "list").remove("-1"), Some(json!(3)));
+    /// assert_eq!((v.path_mut() / "list").remove_at(0), Some(json!(1)));
+    /// assert_eq!(v, json!({"host": {"ip": "127.0.0.1"}, "list": [2]}));
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let v = self.ptr.as_deref_mut()?;
+        settle_embedded(self.embed, self.trail.as_deref(), v);
+        if let Some(key) = self.matcher.actual_key(v, key) {
+            return v.remove_key(&key);
+        }
+        let index = token_index(key, v.get_len())?;
+        v.remove_index(index)
+    }
+
+    /// Remove item of array by index which count from end if negative,
+    /// and return the detached value.
+    pub fn remove_at<I: PathIndex>(&mut self, i: I) -> Option<Value> {
+        let v = self.ptr.as_deref_mut()?;
+        settle_embedded(self.embed, self.trail.as_deref(), v);
+        let index = i.index_in(v.get_len())?;
+        v.remove_index(index)
+    }
+
+    /// Evaluate Relative JSON Pointer from current position.
+    /// As mutable pointer doesn't keep its ancestors, it can only resolve
+    /// down from current node with `0` origin, or get the key or index of
+    /// any ancestor by the `#` form from the tracked path. Otherwise it fails
+    /// with `RelativeError::Upward` that tells the absolute json pointer to
+    /// resolve from root instead.
+    ///
+    /// ```rust
+    /// # use serde_json::json;
+    /// # use json_ops::{ValuePath, Relative, RelativeError};
+    /// let mut v = json!({"service": [{"name": "serv_1"}, {"name": "serv_2", "tags": ["a"]}]});
+    /// let mut serv = v.path_mut() / "service" / 1;
+    /// assert_eq!(serv.relative("0#").unwrap(), Relative::Index(1));
+    /// assert_eq!(serv.relative("1#").unwrap(), Relative::Key("service".to_string()));
+    /// let err = serv.relative("0-1/name").unwrap_err();
+    /// assert_eq!(err, RelativeError::Upward { pointer: "/service/0/name".to_string() });
+    /// let _ = serv.relative("0/tags/0").unwrap().ptr().unwrap() << "b";
+    /// assert_eq!(v["service"][1]["tags"][0], "b");
+    /// ```
+    pub fn relative(&mut self, rel: &str) -> Result<Relative<Self>, RelativeError> {
+        let rel = parse_relative(rel)?;
+        if self.ptr.is_none() {
+            return Err(RelativeError::NoNode);
+        }
+        if rel.up == 0 && rel.shift == 0 {
+            if let Tail::Steps(steps) = &rel.tail {
+                return Ok(Relative::Ptr(self.path_steps(steps)));
+            }
+        }
+
+        // untracked pointer from pointer set is regarded as root
+        let trail = self.trail.clone().unwrap_or_default();
+        let depth = trail.len().checked_sub(rel.up).ok_or(RelativeError::AboveRoot)?;
+        let mut origin = trail[..depth].to_vec();
+        if rel.shift != 0 {
+            match origin.last_mut() {
+                Some(Token::Index(i)) => {
+                    *i = i.checked_add_signed(rel.shift).ok_or(RelativeError::IndexOutOfRange)?;
+                }
+                _ => return Err(RelativeError::NotArrayItem),
+            }
+        }
+
+        match rel.tail {
+            Tail::Name if rel.shift == 0 => match origin.last() {
+                Some(Token::Key(key)) => Ok(Relative::Key(key.clone())),
+                Some(Token::Index(i)) => Ok(Relative::Index(*i)),
+                None => Err(RelativeError::NoName),
+            },
+            Tail::Name => Err(RelativeError::Upward { pointer: join_pointer(&origin) }),
+            Tail::Steps(steps) => {
+                origin.extend(steps.iter().map(|s| Token::Key(s.token())));
+                Err(RelativeError::Upward { pointer: join_pointer(&origin) })
+            }
+        }
     }
 
     /// Convert to immutable pointer, leave self None.
@@ -148,12 +1081,38 @@ where Value: ValuePath + ValueReader + ValueWriter
         ValuePtr::new(Some(v))
     }
 
-    /// Resolve to sub path, by single index.
+    /// Resolve to sub path, by single index which count from end if negative.
     /// Used in operator `/`.
-    fn path_index(&mut self, i: usize) -> Self {
+    fn path_index<I: PathIndex>(&mut self, i: I) -> Self {
         match self.ptr.take() {
-            Some(v) => Self::new(v.get_index_mut(i)),
-            None => Self::new(None)
+            Some(v) => {
+                self.open(v, &[]);
+                let index = i.index_in(v.get_len());
+                if let Some(x) = index.filter(|x| self.create && v.get_index(*x).is_none()) {
+                    let mut next = match entry_item(v, x, self.fresh) {
+                        Ok(next) => self.step(Some(next), &[Token::Index(x)]),
+                        Err(err) => return self.refuse(err),
+                    };
+                    next.fresh = true;
+                    return next;
+                }
+                self.step(index.and_then(|i| v.get_index_mut(i)), &[Token::Index(index.unwrap_or(0))])
+            }
+            None => self.step(None, &[])
+        }
+    }
+
+    /// Resolve to mutable items of array in range.
+    /// Used in operator `/`.
+    fn path_range<R: RangeBounds<usize>>(&mut self, range: R) -> ValuePtrSetMut<'tr, Value> {
+        match self.ptr.take() {
+            Some(v) => {
+                self.open(v, &[]);
+                settle_embedded(self.embed, self.trail.as_deref(), v);
+                let (start, end) = range_bounds(&range, v.get_len());
+                ValuePtrSetMut::new(v.get_slice_mut(start, end))
+            }
+            None => ValuePtrSetMut::new(Vec::new())
         }
     }
 
@@ -161,14 +1120,24 @@ where Value: ValuePath + ValueReader + ValueWriter
     /// Used in operator `/`.
     fn path_str(&mut self, p: &str) -> Self {
         if self.ptr.is_none() {
-            return Self::new(None);
+            return self.step(None, &[]);
         }
 
         // use immutable get to check first, avoid mutable refer twice
         let v = self.ptr.take().unwrap();
-        let target = v.get_key(p);
-        if target.is_some() {
-            Self::new(v.get_key_mut(p))
+        self.open(v, &[]);
+        if let Some(key) = self.matcher.actual_key(v, p) {
+            let target = v.get_key_mut(&key);
+            self.step(target, &[Token::Key(key)])
+        }
+        else if self.create && is_joined(p) && ValuePtr::new(Some(&*v)).with_matcher(self.matcher).pathto(p).is_none() {
+            // create the literal key, rather than split it as path
+            let mut next = match entry_member(v, p) {
+                Ok(next) => self.step(Some(next), &[Token::Key(p.to_string())]),
+                Err(err) => return self.refuse(err),
+            };
+            next.fresh = true;
+            next
         }
         else {
             self.ptr = Some(v); // restore reference had took out to `v`
@@ -177,40 +1146,204 @@ where Value: ValuePath + ValueReader + ValueWriter
     }
 
     /// Resolve to sub path by json pointer syntax but leading '/' is optional.
+    /// Negative index token count from end of array, and the last token `-`
+    /// refer to the new item after the end of array, which can be push by `<<`.
+    /// Token as `key[field=value]` selects item of array by field.
     pub fn pathto(&mut self, p: &str) -> Self {
         if self.ptr.is_none() {
-            return Self::new(None);
+            return self.step(None, &[]);
         }
 
-        let mut fixp = p;
-        if !p.is_empty() && p.chars().nth(0) == Some('/') {
-            fixp = &p[1..];
+        let tokens: Vec<String> = split_loose(p).collect();
+        let mut resolved = Vec::with_capacity(tokens.len());
+        let mut value = self.ptr.take().unwrap();
+        let mut fresh = self.fresh;
+        for (i, token) in tokens.iter().enumerate() {
+            self.open(value, &resolved);
+            if let Some(key) = self.matcher.actual_key(value, token) {
+                value = value.get_key_mut(&key).unwrap();
+                resolved.push(Token::Key(key));
+                fresh = false;
+                continue;
+            }
+            if token == "-" && i + 1 == tokens.len() && is_array_like(value) {
+                return self.append_slot(value, &resolved);
+            }
+            if let Some((key, index)) = hop_predicate(&*value, token, self.matcher) {
+                if let Some(key) = key {
+                    value = value.get_key_mut(&key).unwrap();
+                    resolved.push(Token::Key(key));
+                }
+                value = value.get_index_mut(index).unwrap();
+                resolved.push(Token::Index(index));
+                fresh = false;
+                continue;
+            }
+            let index = token_index(token, value.get_len()).filter(|x| value.get_index(*x).is_some());
+            if let Some(x) = index {
+                value = value.get_index_mut(x).unwrap();
+                resolved.push(Token::Index(x));
+                fresh = false;
+                continue;
+            }
+            if !self.create {
+                return self.step(None, &[]);
+            }
+            match entry(value, token, fresh) {
+                Ok((next, token)) => {
+                    value = next;
+                    resolved.push(token);
+                    fresh = true;
+                }
+                Err(err) => return self.refuse(err),
+            }
+        }
+
+        let mut next = self.step(Some(value), &resolved);
+        next.fresh = fresh;
+        next
+    }
+
+    /// Resolve to sub path by precompiled pointer.
+    /// Used in operator `/`.
+    fn path_pointer(&mut self, p: &Pointer) -> Self {
+        if self.ptr.is_none() {
+            return self.step(None, &[]);
         }
 
-        let target = fixp.split(&['/', '.'][..])
-            .map(|x| x.replace("~1", "/").replace("~0", "~"))
-            .try_fold(self.ptr.take().unwrap(), |value, token| {
-                let try_key = value.get_key(&token);
-                if try_key.is_some() {
-                    value.get_key_mut(&token)
+        let mut resolved = Vec::with_capacity(p.len());
+        let mut value = self.ptr.take().unwrap();
+        let mut fresh = self.fresh;
+        for i in 0..p.len() {
+            let (key, index) = p.step(i);
+            self.open(value, &resolved);
+            if let Some(key) = self.matcher.actual_key(value, key) {
+                value = value.get_key_mut(&key).unwrap();
+                resolved.push(Token::Key(key));
+                fresh = false;
+                continue;
+            }
+            if key == "-" && i + 1 == p.len() && is_array_like(value) {
+                return self.append_slot(value, &resolved);
+            }
+            if let Some((key, index)) = hop_predicate(&*value, key, self.matcher) {
+                if let Some(key) = key {
+                    value = value.get_key_mut(&key).unwrap();
+                    resolved.push(Token::Key(key));
                 }
-                else {
-                    token.parse::<usize>().ok().and_then(|x| value.get_index_mut(x))
+                value = value.get_index_mut(index).unwrap();
+                resolved.push(Token::Index(index));
+                fresh = false;
+                continue;
+            }
+            let index = index.and_then(|x| x.index_in(value.get_len())).filter(|x| value.get_index(*x).is_some());
+            if let Some(x) = index {
+                value = value.get_index_mut(x).unwrap();
+                resolved.push(Token::Index(x));
+                fresh = false;
+                continue;
+            }
+            if !self.create {
+                return self.step(None, &[]);
+            }
+            match entry(value, key, fresh) {
+                Ok((next, token)) => {
+                    value = next;
+                    resolved.push(token);
+                    fresh = true;
                 }
-                //value.get_key_mut(&token).or_else(||
-                //    token.parse::<usize>().ok().and_then(|x| value.get_index_mut(x))
-                //    )
-            });
+                Err(err) => return self.refuse(err),
+            }
+        }
 
-        Self::new(target)
+        let mut next = self.step(Some(value), &resolved);
+        next.fresh = fresh;
+        next
+    }
+
+    /// Resolve to sub path in the specified syntax, `Loose` is the same as
+    /// `pathto()`. The last `-` token in `Pointer` syntax refer to new item
+    /// to append to array.
+    pub fn pathto_with(&mut self, p: &str, syntax: PathSyntax) -> Result<Self, PathSyntaxError> {
+        if syntax == PathSyntax::Loose {
+            return Ok(self.pathto(p));
+        }
+        let steps = parse_path(p, syntax)?;
+        Ok(self.path_steps(&steps))
+    }
+
+    /// Resolve to sub path by parsed steps.
+    fn path_steps(&mut self, steps: &[PathStep]) -> Self {
+        if self.ptr.is_none() {
+            return self.step(None, &[]);
+        }
+
+        let mut resolved = Vec::with_capacity(steps.len());
+        let mut value = self.ptr.take().unwrap();
+        let mut fresh = self.fresh;
+        for (i, step) in steps.iter().enumerate() {
+            self.open(value, &resolved);
+            let hopped = hop(value, step, self.matcher);
+            let created = hopped.is_none();
+            let next = match hopped {
+                Some(Hop::Key(key)) => {
+                    let next = value.get_key_mut(&key);
+                    resolved.push(Token::Key(key));
+                    next
+                }
+                Some(Hop::Index(x)) => {
+                    resolved.push(Token::Index(x));
+                    value.get_index_mut(x)
+                }
+                None if *step == PathStep::Token("-".to_string())
+                    && i + 1 == steps.len() && is_array_like(value) => {
+                    return self.append_slot(value, &resolved);
+                }
+                None if self.create => match entry_step(value, step, fresh) {
+                    Ok((next, token)) => {
+                        resolved.push(token);
+                        Some(next)
+                    }
+                    Err(err) => return self.refuse(err),
+                }
+                None => None,
+            };
+            match next {
+                Some(next) => value = next,
+                None => return self.step(None, &[]),
+            }
+            fresh = created;
+        }
+
+        let mut next = self.step(Some(value), &resolved);
+        next.fresh = fresh;
+        next
+    }
+
+    /// Push a item to the array held in slot, and point to the new item.
+    fn append_value<T>(&mut self, array: &'tr mut Value, val: T) -> Self where Value: From<T> {
+        array.push_array(val);
+        let last = array.get_len().checked_sub(1);
+        self.step(last.and_then(|i| array.get_index_mut(i)), &[Token::Index(last.unwrap_or(0))])
     }
 
     /// Put a value to json and return pointer to it, which may change the node type.
     /// Implement for `<< (val)` , usually in scarlar node.
+    /// If it is resolved by `-` token, append the value to array instead.
     fn put_value<T>(&mut self, rhs: T) -> Self where Value: From<T>, T: ScalarValue {
+        if let Some(array) = self.slot.take() {
+            return self.append_value(array, rhs);
+        }
         match self.ptr.take() {
-            Some(v) => { v.put_value(rhs); Self::new(Some(v)) },
-            None => Self::new(None)
+            Some(v) => {
+                settle_embedded(self.embed, self.trail.as_deref(), v);
+                if let (Some(log), Some(trail)) = (self.embed, self.trail.as_ref()) {
+                    log.forget(trail);
+                }
+                v.put_value(rhs);
+                self.step(Some(v), &[])
+            }
+            None => self.step(None, &[])
         }
     }
 
@@ -218,22 +1351,129 @@ where Value: ValuePath + ValueReader + ValueWriter
     /// Implment for `<< (key, val)`.
     fn push_object<K: ToString, T>(&mut self, key: K, val: T) -> Self where Value: From<T> {
         match self.ptr.take() {
-            Some(v) => { v.push_object(key, val); Self::new(Some(v)) },
-            None => Self::new(None)
+            Some(v) => {
+                settle_embedded(self.embed, self.trail.as_deref(), v);
+                v.push_object(key, val);
+                self.step(Some(v), &[])
+            }
+            None => self.step(None, &[])
         }
     }
 
     /// Push a item to array node, would invalidate the pointer if type mismatch.
     /// Implment for `<< (val, )` or  `<< [item]` .
+    /// If it is resolved by `-` token, append the item to array as well.
     fn push_array<T>(&mut self, val: T) -> Self where Value: From<T> {
+        if let Some(array) = self.slot.take() {
+            return self.append_value(array, val);
+        }
         match self.ptr.take() {
-            Some(v) => { v.push_array(val); Self::new(Some(v)) },
-            None => Self::new(None)
+            Some(v) => {
+                settle_embedded(self.embed, self.trail.as_deref(), v);
+                v.push_array(val);
+                self.step(Some(v), &[])
+            }
+            None => self.step(None, &[])
         }
     }
 
 }
 
+impl<'tr, Value> ValuePtrSet<'tr, Value>
+where Value: ValuePath + ValueReader
+{
+    /// Trivial new constructor.
+    pub fn new(ptrs: Vec<&'tr Value>) -> Self {
+        Self { ptrs, root: None, trail: None, matcher: KeyMatcher::Exact, embed: None }
+    }
+
+    /// Constructor with nodes from the `/` chain of pointer `from`, which
+    /// keeps its root, trail, key matcher and embed cache.
+    fn in_tree(ptrs: Vec<&'tr Value>, from: &ValuePtr<'tr, Value>) -> Self {
+        Self { ptrs, root: from.root, trail: from.trail, matcher: from.matcher, embed: from.embed }
+    }
+
+    /// Another set of nodes in the same chain.
+    fn renew(&self, ptrs: Vec<&'tr Value>) -> Self {
+        Self { ptrs, root: self.root, trail: self.trail, matcher: self.matcher, embed: self.embed }
+    }
+
+    /// Pointer to nothing that keeps the chain of the set, so each node
+    /// moves on in the same way as single pointer.
+    fn origin(&self) -> ValuePtr<'tr, Value> {
+        ValuePtr { root: self.root, trail: self.trail, matcher: self.matcher, embed: self.embed, ..ValuePtr::new(None) }
+    }
+
+    /// Single pointer to one node in the set, which keeps the chain.
+    fn item(&self, v: &'tr Value) -> ValuePtr<'tr, Value> {
+        self.origin().place(v)
+    }
+
+    /// Iterate each node as single pointer.
+    pub fn iter(&self) -> impl Iterator<Item = ValuePtr<'tr, Value>> + '_ {
+        self.ptrs.iter().map(|v| self.item(v))
+    }
+
+    /// Resolve each node by single index, used in operator `/`.
+    fn path_index<I: PathIndex>(&self, i: I) -> Self {
+        self.renew(self.ptrs.iter().filter_map(|v| *self.item(v).path_index(i)).collect())
+    }
+
+    /// Resolve each node by single key or joined path, used in operator `/`.
+    fn path_str(&self, p: &str) -> Self {
+        self.renew(self.ptrs.iter().filter_map(|v| *self.item(v).path_str(p)).collect())
+    }
+
+    /// Expand each node to all of its children, used in operator `/`.
+    fn path_any(&self) -> Self {
+        self.renew(self.ptrs.iter().flat_map(|v| self.item(v).path_any().ptrs).collect())
+    }
+
+    /// Expand each node to children whose key matches, used in operator `/`.
+    fn path_keys<F: Fn(&str) -> bool>(&self, pred: F) -> Self {
+        self.renew(self.ptrs.iter().flat_map(|v| self.item(v).path_keys(&pred).ptrs).collect())
+    }
+
+    /// Read each node as str ref, or default `rhs`.
+    fn get_str(&self, rhs: &'tr str) -> Vec<&'tr str> {
+        self.ptrs.iter().map(|v| v.get_str(rhs)).collect()
+    }
+
+    /// Read each node as String, or default `rhs`.
+    fn get_string(&self, rhs: String) -> Vec<String> {
+        self.ptrs.iter().map(|v| v.get_string(rhs.clone())).collect()
+    }
+
+    /// Read each node as i64, or default `rhs`.
+    fn get_i64(&self, rhs: i64) -> Vec<i64> {
+        self.ptrs.iter().map(|v| v.get_i64(rhs)).collect()
+    }
+
+    /// Read each node as f64, or default `rhs`.
+    fn get_f64(&self, rhs: f64) -> Vec<f64> {
+        self.ptrs.iter().map(|v| v.get_f64(rhs)).collect()
+    }
+
+    /// Read each node as bool, or default `rhs`.
+    fn get_bool(&self, rhs: bool) -> Vec<bool> {
+        self.ptrs.iter().map(|v| v.get_bool(rhs)).collect()
+    }
+}
+
+impl<'tr, Value> ValuePtrSetMut<'tr, Value>
+where Value: ValuePath + ValueReader + ValueWriter
+{
+    /// Trivial new constructor.
+    pub fn new(ptrs: Vec<&'tr mut Value>) -> Self {
+        Self { ptrs }
+    }
+
+    /// Convert to immutable pointer set.
+    pub fn immut(self) -> ValuePtrSet<'tr, Value> {
+        ValuePtrSet::new(self.ptrs.into_iter().map(|v| &*v).collect())
+    }
+}
+
 /* ------------------------------------------------------------ */
 // Split operator overload implemntation to seperate files but not sub mod.
 include!("overload.rs");
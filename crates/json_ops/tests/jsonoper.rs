@@ -464,3 +464,1048 @@ fn ptr_eq_test() {
     assert_eq!(pm, pn);
 }
 
+
+#[test]
+fn query_test() {
+    //! test JSONPath query with examples from RFC 9535.
+    let v = json!({"store": {
+        "book": [
+            {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+            {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+            {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+            {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}
+        ],
+        "bicycle": {"color": "red", "price": 399}
+    }});
+
+    let authors = v.query("$.store.book[*].author").unwrap();
+    assert_eq!(authors, vec!["Nigel Rees", "Evelyn Waugh", "Herman Melville", "J. R. R. Tolkien"]);
+    assert_eq!(v.query("$..author").unwrap(), authors);
+    assert_eq!(v.query("$['store']['book'][*]['author']").unwrap(), authors);
+
+    assert_eq!(v.query("$.store.*").unwrap().len(), 2);
+    assert_eq!(v.query("$.store..price").unwrap().len(), 5);
+    assert_eq!(v.query("$..book[2].author").unwrap(), vec!["Herman Melville"]);
+    assert_eq!(v.query("$..book[-1].title").unwrap(), vec!["The Lord of the Rings"]);
+    assert_eq!(v.query("$..book[0,1].price").unwrap(), vec![8.95, 12.99]);
+    assert_eq!(v.query("$..book[:2].price").unwrap(), vec![8.95, 12.99]);
+    assert_eq!(v.query("$..book[?@.isbn].title").unwrap(), vec!["Moby Dick", "The Lord of the Rings"]);
+    assert_eq!(v.query("$..book[?@.price<10].title").unwrap(), vec!["Sayings of the Century", "Moby Dick"]);
+    assert_eq!(v.query("$..book[?@.price > 10 && @.category == 'fiction'].price").unwrap(), vec![12.99, 22.99]);
+    assert_eq!(v.query("$..book[?!@.isbn || @.price > 20].price").unwrap(), vec![8.95, 12.99, 22.99]);
+    assert_eq!(v.query("$.store.book[?@.price < $.store.bicycle.price].price").unwrap().len(), 4);
+    assert_eq!(v.query("$..*").unwrap().len(), 27);
+    assert_eq!(v.query("$").unwrap(), vec![&v]);
+    assert!(v.query("$.nokey").unwrap().is_empty());
+}
+
+#[test]
+fn query_slice_test() {
+    //! test JSONPath array slice and index selector.
+    let v = json!(["a", "b", "c", "d", "e", "f", "g"]);
+    assert_eq!(v.query("$[1:3]").unwrap(), vec!["b", "c"]);
+    assert_eq!(v.query("$[5:]").unwrap(), vec!["f", "g"]);
+    assert_eq!(v.query("$[1:5:2]").unwrap(), vec!["b", "d"]);
+    assert_eq!(v.query("$[5:1:-2]").unwrap(), vec!["f", "d"]);
+    assert_eq!(v.query("$[::-1]").unwrap(), vec!["g", "f", "e", "d", "c", "b", "a"]);
+    assert_eq!(v.query("$[-2:]").unwrap(), vec!["f", "g"]);
+    assert_eq!(v.query("$[0:0]").unwrap().len(), 0);
+    assert_eq!(v.query("$[::0]").unwrap().len(), 0);
+    assert_eq!(v.query("$[-1, 0, 9]").unwrap(), vec!["g", "a"]);
+    assert_eq!(v.query("$[*]").unwrap().len(), 7);
+}
+
+#[test]
+fn query_error_test() {
+    //! test JSONPath syntax error.
+    let v = json!({"a": [1, 2]});
+    assert!(v.query("a").is_err());
+    assert!(v.query("$.").is_err());
+    assert!(v.query("$[01]").is_err());
+    assert!(v.query("$[-0]").is_err());
+    assert!(v.query("$['a'").is_err());
+    assert!(v.query("$[?@.* == 1]").is_err());
+    assert!(v.query("$[?length(@) > 1]").is_err());
+    assert!(v.query("$.a ").is_err());
+
+    let err = v.query("$.a[1,]").unwrap_err();
+    assert_eq!(err.pos, 6);
+    assert_eq!(err.to_string(), "invalid jsonpath at 6: invalid selector");
+
+    let q = json_ops::JsonPath::parse("$.a[?@ >= 2]").unwrap();
+    assert_eq!(q.select(&v), vec![2]);
+    assert_eq!(v.query(r#"$["a"]"#).unwrap(), vec![&json!([1, 2])]);
+}
+
+#[test]
+fn wildcard_test() {
+    //! test wildcard path token yield pointer set.
+    use json_ops::Wildcard;
+    let v = json!({"a": [{"b": 1, "c": 2}, {"b": 3}, 4], "*": "star"});
+
+    let set = v.path() / "a" / Wildcard;
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.clone() / "b" | 0, vec![1, 3]);
+    assert_eq!(set.clone() / Wildcard | 0, vec![1, 2, 3]);
+    assert_eq!((set.clone() / "*").len(), 0);
+    assert_eq!(set / "b" | 0.0, vec![0.0, 0.0]);
+
+    let set = v.path() / Wildcard;
+    assert_eq!(set.len(), 2);
+    assert_eq!(set[0], &json!("star"));
+    assert_eq!(v.path() / "*" | "", "star");
+
+    let set = v.path() / "a" / Wildcard / "b" / "*";
+    assert!(set.is_empty());
+    let set = v.path() / "a" / 0 / Wildcard | true;
+    assert_eq!(set, vec![true, true]);
+}
+
+#[test]
+fn path_negative_test() {
+    //! test negative index and `-` token to append array.
+    let mut v = json!({"list": [1, 2, 3], "-": "dash"});
+
+    assert_eq!(v.path() / "list" / -1 | 0, 3);
+    assert_eq!(v.path() / "list" / -3_isize | 0, 1);
+    assert_eq!((v.path() / "list" / -4).is_none(), true);
+    assert_eq!(v.pathto("list/-2") | 0, 2);
+    assert_eq!(v.pathto("/list/-") | 0, 0);
+    assert_eq!(v.path() / "-" | "", "dash");
+
+    let i: isize = -1;
+    assert_eq!(v.path_mut() / "list" / i | 0, 3);
+    let _ = v.path_mut() / "list" / -1 << 30;
+    assert_eq!(v["list"], json!([1, 2, 30]));
+
+    // `-` token is valid only for mutable pointer, and append when write
+    let node = v.path_mut() / "list" / "-";
+    assert_eq!(node.is_none(), true);
+    let node = node << 4;
+    assert_eq!(node.is_none(), false);
+    assert_eq!(node | 0, 4);
+    let _ = v.pathto_mut("/list/-") << [5];
+    let _ = v.path_mut() / "list" / "-" << ("six",);
+    assert_eq!(v["list"], json!([1, 2, 30, 4, 5, "six"]));
+
+    // `-` only in the end and without write has no effect
+    let _ = v.path_mut() / "list" / "-";
+    let _ = v.path_mut() / "list/-/0" << 7;
+    let _ = v.path_mut() / "list" / "-" << ("k", "v");
+    assert_eq!(v["list"].as_array().unwrap().len(), 6);
+    let _ = v.path_mut() / "-" << "DASH";
+    assert_eq!(v["-"], "DASH");
+
+    // empty object is not array to append, but empty array is
+    let mut v = json!({"o": {}, "a": []});
+    let _ = v.path_mut() / "o" / "-" << 5;
+    let _ = v.pathto_mut("a/-") << 5;
+    assert_eq!(v, json!({"o": {}, "a": [5]}));
+}
+
+#[test]
+fn path_range_test() {
+    //! test range slice of array.
+    let mut v = json!({"a": [0, 1, 2, 3, 4], "o": {"0": 0}});
+
+    let set = v.path() / "a" / (1..3);
+    assert_eq!(set.len(), 2);
+    assert_eq!(set[0], &json!(1));
+    assert_eq!(set | 0, vec![1, 2]);
+    assert_eq!(v.path() / "a" / (3..=4) | 0, vec![3, 4]);
+    assert_eq!(v.path() / "a" / (..2) | 0, vec![0, 1]);
+    assert_eq!(v.path() / "a" / (..=usize::MAX) | 0, vec![0, 1, 2, 3, 4]);
+    assert_eq!((v.path() / "o" / (..)).len(), 0);
+    assert_eq!((v.path() / "none" / (..)).len(), 0);
+
+    let mut set = v.path_mut() / "a" / (3..);
+    assert_eq!(set.len(), 2);
+    *set[0] = json!("three");
+    let _ = v.path_mut() / "a" / (..1) | 0;
+    assert_eq!(v["a"], json!([0, 1, 2, "three", 4]));
+    assert_eq!(v.path_mut() / "a" / (2..4) | "".to_string(), vec!["2", "three"]);
+}
+
+#[test]
+fn location_test() {
+    //! test the path where pointer refers to.
+    use json_ops::{PathTrail, Wildcard};
+    let mut v = json!({"service": [{"name": "s1"}, {"name": "s2", "desc": "d2"}],
+        "a/b": {"m~n": 1}});
+
+    assert_eq!((v.path() / "service" / 1).location(), None);
+    let trail = PathTrail::new();
+    let root = v.path().with_trail(&trail);
+    assert_eq!(root.location(), Some(String::new()));
+    assert_eq!((root / "service" / 1 / "desc").location(), Some("/service/1/desc".to_string()));
+    assert_eq!((root / "service" / -1).location(), Some("/service/1".to_string()));
+    assert_eq!(root.pathto("service.0.name").location(), Some("/service/0/name".to_string()));
+    assert_eq!((root / "a/b" / "m~n").location(), Some("/a~1b/m~0n".to_string()));
+    assert_eq!((root / "service" / 2).location(), None);
+    let names: Vec<_> = (root / "service" / (..) / "name").iter().map(|p| p.location().unwrap()).collect();
+    assert_eq!(names, vec!["/service/0/name", "/service/1/name"]);
+    let names: Vec<_> = (root / Wildcard / Wildcard / "desc").into_iter().map(|p| p.location().unwrap()).collect();
+    assert_eq!(names, vec!["/service/1/desc"]);
+
+    let node = v.path_mut() / "service" / -1 / "desc";
+    assert_eq!(node.location(), Some("/service/1/desc".to_string()));
+    let node = node << "new desc";
+    assert_eq!(node.location(), Some("/service/1/desc".to_string()));
+    let node = v.path_mut() / "service" / "-" << "s3";
+    assert_eq!(node.location(), Some("/service/2".to_string()));
+    let node = v.path_mut() / "service" / 0 << ("desc", "d1");
+    assert_eq!((node / "desc").location(), Some("/service/0/desc".to_string()));
+    assert_eq!(v.pathto_mut("/a~1b/m~0n").location(), Some("/a~1b/m~0n".to_string()));
+    assert_eq!((v.path_mut() / "none").location(), None);
+}
+
+#[test]
+fn path_error_test() {
+    //! test explain why path resolved to none.
+    use json_ops::{PathErrorKind, NodeKind};
+    let v = json!({"host": {"port": 8080, "Protocol": ["tcp"]}, "name": "json"});
+
+    assert_eq!((v.path() / "host" / "port").error(), None);
+    assert_eq!(ValuePtr::<serde_json::Value>::new(None).error(), None);
+
+    let err = (v.path() / "host" / "prot" / 0).error().unwrap();
+    assert_eq!(err.segment, 1);
+    assert_eq!(err.token, "prot");
+    assert_eq!(err.kind, PathErrorKind::MissingKey);
+    assert_eq!(err.found, NodeKind::Object);
+    assert_eq!(err.suggestions, vec!["port"]);
+
+    let err = v.pathto("/host/protocol/0").error().unwrap();
+    assert_eq!(err.segment, 1);
+    assert_eq!(err.suggestions, vec!["Protocol"]);
+
+    let err = (v.path() / "host" / "Protocol" / 3).error().unwrap();
+    assert_eq!(err.segment, 2);
+    assert_eq!(err.token, "3");
+    assert_eq!(err.kind, PathErrorKind::IndexOutOfRange { len: 1 });
+    assert_eq!(err.found, NodeKind::Array);
+    assert_eq!(err.to_string(), "segment 2 `3`: index out of range for length 1 at array node");
+    let err = (v.path() / "host" / "Protocol" / -2).error().unwrap();
+    assert_eq!(err.token, "-2");
+    let err = (v.path() / "host/Protocol" / "first").error().unwrap();
+    assert_eq!(err.segment, 2);
+    assert_eq!(err.kind, PathErrorKind::NotIndex);
+
+    let err = (v.path() / "name" / "first").error().unwrap();
+    assert_eq!(err.segment, 1);
+    assert_eq!(err.kind, PathErrorKind::NotContainer);
+    assert_eq!(err.found, NodeKind::String);
+
+    let long = "a_very_long_key_that_not_exists_at_all";
+    let err = (v.path() / long).error().unwrap();
+    assert_eq!(err.token.ends_with("..."), true);
+    assert_eq!(long.starts_with(err.token.trim_end_matches("...")), true);
+    assert_eq!((v.path() / "nmae").error().unwrap().suggestions, vec!["name"]);
+    assert_eq!((v.path() / "xyz").error().unwrap().suggestions.len(), 0);
+
+    // long token is still compared in full for suggestions
+    let pool = json!({"max_connections_per_host": 8, "idle_timeout": 64});
+    let err = (pool.path() / "max_connection_per_host").error().unwrap();
+    assert_eq!(err.token.ends_with("..."), true);
+    assert_eq!(err.suggestions, vec!["max_connections_per_host"]);
+    assert_eq!((pool.path() / "max_connections_per_hots").error().unwrap().suggestions, vec!["max_connections_per_host"]);
+
+    let miss = v.path() / "nmae";
+    assert_eq!((miss / "first").error().unwrap().token, "nmae");
+    assert_eq!(miss.or(v.path()).error().is_none(), true);
+}
+
+#[test]
+fn path_syntax_test() {
+    //! test pathto in different syntax.
+    use json_ops::PathSyntax;
+    let mut v = json!({"v1.2": {"list": [10, 20, 30], "a/b": 1, "m~n": 2},
+        "a": {"b": [{"c": "abc"}], "0": "zero"}});
+
+    // default loose syntax can't reach key with dot
+    assert_eq!(v.pathto("v1.2/list/0").is_none(), true);
+    assert_eq!(v.path().pathto_with("a.b.0.c", PathSyntax::default()).unwrap() | "", "abc");
+
+    let ptr = PathSyntax::Pointer;
+    assert_eq!(v.pathto_with("/v1.2/list/1", ptr).unwrap() | 0, 20);
+    assert_eq!(v.pathto_with("/v1.2/a~1b", ptr).unwrap() | 0, 1);
+    assert_eq!(v.pathto_with("/v1.2/m~0n", ptr).unwrap() | 0, 2);
+    assert_eq!(v.pathto_with("", ptr).unwrap() == v.path(), true);
+    assert_eq!(v.pathto_with("/v1.2/list/-1", ptr).unwrap().is_none(), true);
+    assert_eq!(v.pathto_with("/v1.2/list/01", ptr).unwrap().is_none(), true);
+    assert_eq!(v.pathto_with("/a/0", ptr).unwrap() | "", "zero");
+    let err = v.pathto_with("v1.2", ptr).unwrap_err();
+    assert_eq!(err.pos, 0);
+    let err = v.pathto_with("/a~2b", ptr).unwrap_err();
+    assert_eq!(err.pos, 2);
+    assert_eq!(err.to_string(), "invalid pointer path at 2: `~` must be followed by `0` or `1`");
+
+    let dot = PathSyntax::Dotted;
+    assert_eq!(v.pathto_with("a.b[0].c", dot).unwrap() | "", "abc");
+    assert_eq!(v.pathto_with("a.b[-1].c", dot).unwrap() | "", "abc");
+    assert_eq!(v.pathto_with("a.0", dot).unwrap() | "", "zero");
+    assert_eq!(v.pathto_with("a.b.0", dot).unwrap().is_none(), true);
+    assert_eq!(v.pathto_with("v1.2", dot).unwrap().is_none(), true);
+    assert_eq!(v.pathto_with("a..b", dot).unwrap_err().pos, 2);
+    assert_eq!(v.pathto_with("a.b[x]", dot).unwrap_err().pos, 4);
+    assert_eq!(v.pathto_with("a.b[0", dot).unwrap_err().msg, "expect `]`");
+    assert_eq!(v.pathto_with(r#"a["b"]"#, dot).is_err(), true);
+
+    let brk = PathSyntax::Bracket;
+    assert_eq!(v.pathto_with(r#"["v1.2"].list[2]"#, brk).unwrap() | 0, 30);
+    assert_eq!(v.pathto_with(r#"["v1.2"]['a/b']"#, brk).unwrap() | 0, 1);
+    assert_eq!(v.pathto_with(r#"a["b"][0]["c"]"#, brk).unwrap() | "", "abc");
+    assert_eq!(v.pathto_with(r#"a["x"]"#, brk).unwrap().error().unwrap().segment, 1);
+    assert_eq!(v.pathto_with(r#"a["b]"#, brk).unwrap_err().msg, "unclosed quoted key");
+    assert_eq!(v.pathto_with(r#"a["\n"]"#, brk).unwrap_err().msg, "invalid escape in quoted key");
+
+    let _ = v.pathto_mut_with(r#"["v1.2"].list[-1]"#, brk).unwrap() << 300;
+    let node = v.pathto_mut_with("/v1.2/list/-", ptr).unwrap() << 40;
+    assert_eq!(node.location(), Some("/v1.2/list/3".to_string()));
+    assert_eq!(v["v1.2"]["list"], json!([10, 20, 300, 40]));
+    assert_eq!(v.pathto_mut_with("a.b[0].c", dot).unwrap().location(), Some("/a/b/0/c".to_string()));
+    assert_eq!(v.pathto_mut_with("a.b[", dot).is_err(), true);
+}
+
+#[test]
+fn pointer_type_test() {
+    //! test precompiled pointer.
+    use json_ops::Pointer;
+    static DESC: Pointer = Pointer::from_static(&["service", "-1", "a/b"], &[None, Some(-1), None]);
+    let mut v = json!({"service": [{"name": "s1"}, {"name": "s2", "a/b": "ab"}]});
+
+    let name = Pointer::new("/service/0/name");
+    assert_eq!(name.len(), 3);
+    assert_eq!(name.to_string(), "/service/0/name");
+    assert_eq!(v.path() / &name | "", "s1");
+    assert_eq!(name.resolve(&v) == v.pathto("/service/0/name"), true);
+    assert_eq!(v.path() / "service" / &Pointer::new("1.a~1b") | "", "ab");
+    assert_eq!(v.path() / &DESC | "", "ab");
+    assert_eq!(DESC.to_string(), "/service/-1/a~1b");
+    assert_eq!(Pointer::from("").is_empty(), false);
+
+    let err = (v.path() / &Pointer::new("service/2/name")).error().unwrap();
+    assert_eq!(err.segment, 1);
+    assert_eq!(err.token, "2");
+
+    let _ = name.resolve_mut(&mut v) << "S1";
+    assert_eq!(v.path() / &name | "", "S1");
+    assert_eq!((v.path_mut() / &DESC).location(), Some("/service/1/a~1b".to_string()));
+    let node = v.path_mut() / &Pointer::new("service/-") << "s3";
+    assert_eq!(node.location(), Some("/service/2".to_string()));
+
+    // predicate is one token, the same as `pathto()`
+    let mut v = json!({"host": [{"name": "a.b", "port": 80}, {"name": "c/d", "port": 81}]});
+    let port = Pointer::new("host[name=a.b]/port");
+    assert_eq!(port.len(), 2);
+    assert_eq!(v.path() / &port | 0, 80);
+    assert_eq!(v.path() / &Pointer::new("host[name='c/d'].port") | 0, 81);
+    let _ = v.path_mut() / &port << 8080;
+    assert_eq!(v.pathto("host[name=a.b]/port") | 0, 8080);
+}
+
+#[test]
+fn path_macro_test() {
+    //! test compile time path macro.
+    use json_ops::path;
+    let mut v = json!({"usr": {"lib": [0, 1, {"name": "libc"}]}, "a/b": {"m~n": 1}, "": 2});
+
+    let ptr = path!("usr/lib/2/name");
+    assert_eq!(ptr.keys().collect::<Vec<_>>(), vec!["usr", "lib", "2", "name"]);
+    assert_eq!(v.path() / ptr | "", "libc");
+    assert_eq!(v.path() / path!("/usr.lib.-2") | -1, 1);
+    assert_eq!(v.path() / path!("a~1b/m~0n") | 0, 1);
+    assert_eq!(path!("a~1b/m~0n").to_string(), "/a~1b/m~0n");
+    assert_eq!(v.path() / path!("") | 0, 2);
+    assert_eq!((v.path() / path!("usr/lib/3")).error().unwrap().segment, 2);
+
+    let _ = v.path_mut() / path!("usr/lib/-") << 3;
+    assert_eq!(v.path() / "usr" / "lib" / 3 | 0, 3);
+
+    let ptr = path!("usr/lib[name=libc.so]/name");
+    assert_eq!(ptr.keys().collect::<Vec<_>>(), vec!["usr", "lib[name=libc.so]", "name"]);
+    let _ = v.path_mut() / "usr" / "lib" / 2 / "name" << "libc.so";
+    assert_eq!(v.path() / ptr | "", "libc.so");
+    assert_eq!(path!("a[x/y]z/b[c.d").keys().collect::<Vec<_>>(), vec!["a[x/y]z", "b[c", "d"]);
+    assert_eq!(path!("a[x/y]z/b[c.d").to_string(), json_ops::Pointer::new("a[x/y]z/b[c.d").to_string());
+
+    // index hints are parsed at compile time, the same as at runtime
+    const TOKENS: [&str; 9] = ["1", "-2", "+3", "x", "-", "", "1e3", "9223372036854775808", "-9223372036854775808"];
+    let hints = json_ops::__path::indexes(&TOKENS);
+    let parsed: Vec<Option<isize>> = TOKENS.iter().map(|t| t.parse::<isize>().ok()).collect();
+    assert_eq!(hints.to_vec(), parsed);
+}
+
+#[test]
+fn cursor_test() {
+    //! test cursor move up and side.
+    let v = json!({"a": 1, "b": [10, 20, 30], "c": {"d": "dd"}});
+
+    let root = v.cursor();
+    assert_eq!(root.depth(), 0);
+    assert_eq!(root.parent().is_none(), true);
+    assert_eq!(root.key(), None);
+    assert_eq!(root.next_sibling().is_none(), true);
+
+    let b1 = root.child("b").unwrap().item(1).unwrap();
+    assert_eq!(*b1, json!(20));
+    assert_eq!(b1.index(), Some(1));
+    assert_eq!(b1.key(), None);
+    assert_eq!(b1.location(), "/b/1");
+    assert_eq!(*b1.prev_sibling().unwrap(), json!(10));
+    assert_eq!(*b1.next_sibling().unwrap(), json!(30));
+    assert_eq!(b1.next_sibling().unwrap().next_sibling().is_none(), true);
+    assert_eq!(b1.prev_sibling().unwrap().prev_sibling().is_none(), true);
+
+    let b = b1.parent().unwrap();
+    assert_eq!(b.key(), Some("b"));
+    assert_eq!(b.prev_sibling().unwrap().key(), Some("a"));
+    assert_eq!(b.next_sibling().unwrap().key(), Some("c"));
+    assert_eq!(b1.root().value() == &v, true);
+    assert_eq!(root.child("none").is_none(), true);
+    assert_eq!(root.item(0).is_none(), true);
+
+    let trail = json_ops::PathTrail::new();
+    let d = (v.path().with_trail(&trail) / "c" / "d").cursor().unwrap();
+    assert_eq!(d.location(), "/c/d");
+    assert_eq!(d.ptr() | "", "dd");
+    assert_eq!(d.parent().unwrap().prev_sibling().unwrap().location(), "/b");
+    assert_eq!((v.path() / "none").cursor().is_none(), true);
+}
+
+#[test]
+fn relative_test() {
+    //! test relative json pointer.
+    use json_ops::{Relative, RelativeError, PathSyntax, PathTrail};
+    let mut v = json!({"service": [{"name": "s1", "tags": ["a", "b"]}, {"name": "s2", "port": 80}]});
+
+    assert_eq!((v.path() / "service" / 0).relative("0#"), Err(RelativeError::NoNode));
+    let trail = PathTrail::new();
+    let tag = v.path().with_trail(&trail) / "service" / 0 / "tags" / 1;
+    assert_eq!(tag.relative("0").unwrap(), Relative::Ptr(tag));
+    assert_eq!(tag.relative("0#").unwrap(), Relative::Index(1));
+    assert_eq!(tag.relative("0-1").unwrap().ptr().unwrap() | "", "a");
+    assert_eq!(tag.relative("1#").unwrap(), Relative::Key("tags".to_string()));
+    assert_eq!(tag.relative("2/name").unwrap().ptr().unwrap() | "", "s1");
+    assert_eq!(tag.relative("2+1/port").unwrap().ptr().unwrap() | 0, 80);
+    assert_eq!(tag.relative("2+1#").unwrap(), Relative::Index(1));
+    assert_eq!(tag.relative("4").unwrap().ptr().unwrap().location(), Some(String::new()));
+    assert_eq!(tag.relative("2/none").unwrap().ptr().unwrap().error().unwrap().segment, 2);
+
+    assert_eq!(tag.relative("5"), Err(RelativeError::AboveRoot));
+    assert_eq!(tag.relative("4#"), Err(RelativeError::NoName));
+    assert_eq!(tag.relative("0+1"), Err(RelativeError::IndexOutOfRange));
+    assert_eq!(tag.relative("1+1"), Err(RelativeError::NotArrayItem));
+    assert_eq!((v.path() / "none").relative("0"), Err(RelativeError::NoNode));
+    for bad in ["", "01", "-1", "1+0", "1name", "0/a~2", "0##"] {
+        assert_eq!(matches!(tag.relative(bad), Err(RelativeError::Syntax { .. })), true, "{}", bad);
+    }
+
+    let mut tags = v.path_mut() / "service" / 0 / "tags";
+    assert_eq!(tags.relative("0#").unwrap(), Relative::Key("tags".to_string()));
+    assert_eq!(tags.relative("1#").unwrap(), Relative::Index(0));
+    assert_eq!(tags.relative("3#"), Err(RelativeError::NoName));
+    assert_eq!(tags.relative("4#"), Err(RelativeError::AboveRoot));
+    let err = tags.relative("1+1/name").unwrap_err();
+    assert_eq!(err, RelativeError::Upward { pointer: "/service/1/name".to_string() });
+    let node = tags.relative("0/1").unwrap().ptr().unwrap() << "B";
+    assert_eq!(node.location(), Some("/service/0/tags/1".to_string()));
+    assert_eq!(v["service"][0]["tags"], json!(["a", "B"]));
+
+    // move up by resolving the absolute pointer from mutable root
+    let _ = v.pathto_mut_with("/service/1/name", PathSyntax::Pointer).unwrap() << "S2";
+    assert_eq!(v["service"][1]["name"], "S2");
+}
+
+#[test]
+fn find_paths_test() {
+    //! test reverse lookup of node paths.
+    use json_ops::{find_paths, Needle};
+    let v = json!({"a/b": {"port": 80, "tags": ["x"]}, "list": [80, 80.0, "80", {"tags": ["x"]}]});
+
+    assert_eq!(find_paths(&v, Needle::Equal(&json!(80))), vec!["/a~1b/port", "/list/0", "/list/1"]);
+    assert_eq!(find_paths(&v, Needle::Equal(&json!({"tags": ["x"]}))), vec!["/list/3"]);
+    assert_eq!(find_paths(&v, Needle::Equal(&json!(["x"]))), vec!["/a~1b/tags", "/list/3/tags"]);
+    assert_eq!(find_paths(&v, Needle::Equal(&v)), vec![""]);
+    assert_eq!(find_paths(&v, Needle::Equal(&json!(null))).len(), 0);
+    let e = json!({"a": [], "o": {}});
+    assert_eq!(find_paths(&e, Needle::Equal(&json!({}))), vec!["/o"]);
+    assert_eq!(find_paths(&e, Needle::Equal(&json!([]))), vec!["/a"]);
+
+    let node = (v.path() / "list" / 3 / "tags" / 0).unwrap();
+    assert_eq!(find_paths(&v, Needle::Node(node)), vec!["/list/3/tags/0"]);
+    let other = json!("x");
+    assert_eq!(find_paths(&v, Needle::Node(&other)).len(), 0);
+    assert_eq!(find_paths(&v, Needle::Equal(&other)).len(), 2);
+}
+
+#[test]
+fn key_matcher_test() {
+    //! test loose key matching.
+    use json_ops::{KeyMatcher, PathSyntax, PathTrail, Wildcard};
+    let mut v = json!({"Port": 80, "port": 8080, "Server": {"max-conns": 100, "maxConns": 200}});
+    let trail = PathTrail::new();
+
+    assert_eq!(KeyMatcher::Normalized.matches("max_conns", "MaxConns"), true);
+    assert_eq!(KeyMatcher::IgnoreCase.matches("max_conns", "Max-Conns"), false);
+    assert_eq!((v.path() / "server").is_none(), true);
+
+    let root = v.path().with_trail(&trail).with_matcher(KeyMatcher::IgnoreCase);
+    assert_eq!(root / "port" | 0, 8080);
+    assert_eq!(root / "PORT" | 0, 80);
+    assert_eq!((root / "PORT").matched_key(), Some("Port"));
+    assert_eq!(root / "server" / "MAXCONNS" | 0, 200);
+    assert_eq!((root / "server" / "max_conns").is_none(), true);
+    assert_eq!(root.pathto("server/Max-Conns") | 0, 100);
+
+    let root = v.path().with_trail(&trail).with_matcher(KeyMatcher::Normalized);
+    assert_eq!(root / "server" / "max_conns" | 0, 100);
+    assert_eq!((root / "server" / "max_conns").matched_key(), Some("max-conns"));
+    assert_eq!(root.pathto("/SERVER/max-conns").location(), Some("/Server/max-conns".to_string()));
+    assert_eq!(root.pathto_with("server.MaxConns", PathSyntax::Dotted).unwrap() | 0, 100);
+    assert_eq!((root / "server" / "max_conn").error().unwrap().suggestions, vec!["max-conns"]);
+    assert_eq!((root / &json_ops::Pointer::new("server/max__conns")) | 0, 100);
+    assert_eq!(root.matched_key(), None);
+
+    let node = v.path_mut().with_matcher(KeyMatcher::Normalized) / "server" / "MAX_CONNS";
+    assert_eq!(node.matched_key(), Some("max-conns"));
+    let node = node << 150;
+    assert_eq!(node.location(), Some("/Server/max-conns".to_string()));
+    let _ = v.path_mut().with_matcher(KeyMatcher::IgnoreCase).pathto("server/maxconns") << 250;
+    assert_eq!(v["Server"], json!({"max-conns": 150, "maxConns": 250}));
+    let node = v.path_mut().with_matcher(KeyMatcher::IgnoreCase).pathto_with("/SERVER/maxconns", PathSyntax::Pointer).unwrap();
+    assert_eq!(node.matched_key(), Some("maxConns"));
+
+    // pointer set keeps the matcher for each node
+    let v = json!({"Service": [{"Name": "a"}, {"name": "b"}]});
+    let root = v.path().with_trail(&trail).with_matcher(KeyMatcher::IgnoreCase);
+    assert_eq!(root / "service" / Wildcard / "name" | "", vec!["a", "b"]);
+    assert_eq!(root / "service" / (..) / "NAME" | "", vec!["a", "b"]);
+    let names: Vec<_> = (root / "service" / Wildcard / "name").into_iter().map(|p| p.matched_key().unwrap()).collect();
+    assert_eq!(names, vec!["Name", "name"]);
+}
+
+#[test]
+fn glob_key_test() {
+    //! test selecting keys by glob or regex pattern.
+    use json_ops::Glob;
+    let v = json!({"db_main": {"port": 3306}, "db_log": {"port": 3307}, "cache": {"port": 6379},
+        "group": [{"db_a": 1, "dbx": 2}, {"db_b": 3}]});
+
+    assert_eq!(Glob("db_*").is_match("db_"), true);
+    assert_eq!(Glob("db_?").is_match("db_ab"), false);
+    assert_eq!(Glob("[a-c]*").is_match("cache"), true);
+    assert_eq!(Glob("[!a-c]*").is_match("cache"), false);
+    assert_eq!(Glob("a\\*").is_match("a*"), true);
+    assert_eq!(Glob("a\\*").is_match("ab"), false);
+    assert_eq!(Glob("*_*_*").is_match("a_b"), false);
+
+    let ports = v.path() / Glob("db_*") / "port";
+    assert_eq!(ports.len(), 2);
+    assert_eq!(ports | 0, vec![3307, 3306]);
+    assert_eq!(v.path() / Glob("*") / "port" | 0, vec![6379, 3307, 3306]);
+    assert_eq!(v.path() / "group" / json_ops::Wildcard / Glob("db*") | 0, vec![1, 2, 3]);
+    assert_eq!(v.path() / "group" / 0 / Glob("db_?") | 0, vec![1]);
+    assert_eq!((v.path() / "nothing" / Glob("*")).len(), 0);
+    assert_eq!((v.path() / "group" / Glob("*")).len(), 0);
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn regex_key_test() {
+    use regex::Regex;
+    let v = json!({"db_1": {"port": 3306}, "db_2": {"port": 3307}, "db_x": {"port": 0}});
+    let re = Regex::new(r"^db_\d$").unwrap();
+    assert_eq!(v.path() / &re / "port" | 0, vec![3306, 3307]);
+    assert_eq!(v.path() / json_ops::Wildcard / &Regex::new("^po").unwrap() | -1, vec![3306, 3307, 0]);
+}
+
+#[test]
+fn descendants_test() {
+    //! test recursive search in subtree.
+    let v = json!({"name": "root", "service": [{"name": "serv_1", "port": 8080},
+        {"name": "serv_2", "host": {"name": "h2", "port": 80}}], "a/b": {"name": "slash"}});
+
+    let names: Vec<_> = v.path().descendants_named("name").map(|(path, _)| path).collect();
+    assert_eq!(names, vec!["/a~1b/name", "/name", "/service/0/name", "/service/1/host/name", "/service/1/name"]);
+    let names: Vec<_> = (v.path() / "service" / 1).descendants_named("name").map(|(_, node)| node).collect();
+    assert_eq!(names, vec!["h2", "serv_2"]);
+    assert_eq!((v.path() / "nothing").descendants_named("name").count(), 0);
+    assert_eq!((v.path() / "name").descendants_named("name").count(), 0);
+
+    let ports: Vec<_> = v.path().descendants_where(|path, node| path.ends_with("/port") && node.as_i64() > Some(100)).collect();
+    assert_eq!(ports, vec![("/service/0/port".to_string(), &json!(8080))]);
+    let items = v.path().descendants_where(|_, node| node.is_object()).count();
+    assert_eq!(items, 4);
+    let mut seen = 0;
+    let first = v.path().descendants_where(|_, _| { seen += 1; true }).next();
+    assert_eq!(first.unwrap().0, "/a~1b");
+    assert_eq!(seen, 1);
+}
+
+#[test]
+fn fallback_test() {
+    //! test fallback between pointers.
+    let v = json!({"host": {"ip": "10.0.0.1", "port": 80}, "ip": "127.0.0.1", "port": "8080", "timeout": 30});
+
+    assert_eq!((v.path() / "host" / "ip").or(v.path() / "ip") | "0.0.0.0", "10.0.0.1");
+    assert_eq!((v.path() / "serv" / "ip").or(v.path() / "ip") | "0.0.0.0", "127.0.0.1");
+    assert_eq!((v.path() / "serv" / "ip").or(v.path() / "addr") | "0.0.0.0", "0.0.0.0");
+    assert_eq!(v.path() / "serv" / "port" | v.path() / "host" / "port" | v.path() / "port" | 0, 80);
+    assert_eq!(v.path() / "serv" / "timeout" | v.path() / "timeout" | 10, 30);
+    assert_eq!(v.path() / "serv" / "retry" | v.path() / "retry" | 3, 3);
+
+    // the fallback is still a pointer in the same tree
+    let trail = json_ops::PathTrail::new();
+    let root = v.path().with_trail(&trail);
+    let port = root / "serv" / "port" | root / "port";
+    assert_eq!(port.location(), Some("/port".to_string()));
+    assert_eq!(port | 0, 8080);
+    let none = v.path() / "serv" | v.path() / "addr";
+    assert_eq!(none.is_none(), true);
+    assert_eq!(none.error().unwrap().token, "addr");
+}
+
+#[test]
+fn layered_test() {
+    //! test layered view over several trees.
+    use json_ops::LayeredPtr;
+    let defaults = json!({"host": {"ip": "0.0.0.0", "port": 80, "tags": ["a", "b"]}, "log": {"level": "info"}, "debug": false});
+    let system = json!({"host": {"port": 8000, "tags": ["c"]}, "log": "syslog"});
+    let user = json!({"host": {"ip": "127.0.0.1"}, "name": "me"});
+    let env = json!({"host": {"port": "9090"}});
+    let conf = LayeredPtr::new(vec![&defaults, &system, &user, &env]);
+
+    assert_eq!(conf.origin(), Some(3));
+    assert_eq!(conf.keys(), vec!["debug", "host", "log", "name"]);
+    let host = conf.clone() / "host";
+    assert_eq!(host.keys(), vec!["ip", "port", "tags"]);
+    assert_eq!(host.clone() / "port" | 0, 9090);
+    assert_eq!((host.clone() / "port").origin(), Some(3));
+    assert_eq!(host.clone() / "ip" | "", "127.0.0.1");
+    assert_eq!((host.clone() / "ip").origin(), Some(2));
+    assert_eq!(host.clone() / "tags" / 0 | "", "c");
+    assert_eq!(host.clone() / "tags" / -1 | "", "c");
+    assert_eq!((host.clone() / "tags" / 1).is_none(), true);
+    assert_eq!(conf.clone() / "debug" | true, false);
+    assert_eq!((conf.clone() / "debug").origin(), Some(0));
+
+    // scalar in higher layer shadows object in lower layer
+    assert_eq!(conf.clone() / "log" | "", "syslog");
+    assert_eq!((conf.clone() / "log" / "level").is_none(), true);
+    assert_eq!((conf.clone() / "log/level").is_none(), true);
+    assert_eq!((conf.clone() / "log" / "level").origin(), None);
+
+    assert_eq!(conf.clone() / "host/ip" | "", "127.0.0.1");
+    assert_eq!(conf.clone() / "/host/tags/0" | "", "c");
+    assert_eq!(conf.clone() / "host.port".to_string() | 0, 9090);
+    let miss = conf.clone() / "host" / "prot";
+    assert_eq!(miss.origin(), None);
+    assert_eq!(miss.error().unwrap().suggestions, vec!["port"]);
+    assert_eq!(*(conf.clone() / "name").ptr(), Some(&json!("me")));
+
+    // joined path in the syntax of `pathto()`, with predicate
+    let base = json!({"service": [{"name": "a", "ip": "1.2.3.4"}, {"name": "b", "ip": "5.6.7.8"}]});
+    let over = json!({"a/b": {"c": 1}});
+    let conf = LayeredPtr::new(vec![&base, &over]);
+    assert_eq!(conf.clone() / "service[ip=5.6.7.8]/name" | "", "b");
+    assert_eq!((conf.clone() / "service[ip=5.6.7.8]/name").origin(), Some(0));
+    assert_eq!(conf.clone() / "service[name=a].ip" | "", "1.2.3.4");
+    assert_eq!(conf.clone() / "a~1b/c" | 0, 1);
+    assert_eq!((conf.clone() / "service[name=c]/ip").origin(), None);
+}
+
+#[test]
+fn predicate_test() {
+    //! test selecting array item by field value in path.
+    let mut v = json!({"service": [
+        {"name": "serv_1", "port": 80, "ip": "10.0.0.1", "tls": false},
+        {"name": "serv_2", "port": 8080, "ip": "10.0.0.2", "tls": true},
+        {"name": "80", "port": 90, "ip": "10.0.0.3", "tls": null}
+    ], "a[b": {"c": 1}});
+
+    assert_eq!(v.pathto("service[name=serv_2]/port") | 0, 8080);
+    assert_eq!(v.pathto("/service[port=80]/name") | "", "serv_1");
+    assert_eq!(v.pathto("service[ip=10.0.0.2].name") | "", "serv_2");
+    assert_eq!(v.pathto("service[tls=true]/name") | "", "serv_2");
+    assert_eq!(v.pathto("service[tls=null]/name") | "", "80");
+    assert_eq!(v.pathto("service[name='80']/port") | 0, 90);
+    assert_eq!(v.pathto("service[port=\"80\"]").is_none(), true);
+    assert_eq!(v.pathto("service/[name=serv_1]/port") | 0, 80);
+    assert_eq!(v.path() / "service[name=serv_2]" / "ip" | "", "10.0.0.2");
+    assert_eq!(v.path() / "service" / "[name=serv_2]" / "ip" | "", "10.0.0.2");
+    let trail = json_ops::PathTrail::new();
+    assert_eq!((v.path().with_trail(&trail) / "service[name=serv_2]").location(), Some("/service/1".to_string()));
+    assert_eq!(v.pathto("service[name=serv_3]/port").is_none(), true);
+    assert_eq!(v.pathto("service[=serv_1]").is_none(), true);
+    assert_eq!(v.pathto("a[b/c") | 0, 1);
+
+    let _ = v.pathto_mut("service[name=serv_2]/port") << 9090;
+    assert_eq!(v["service"][1]["port"], 9090);
+    let node = v.pathto_mut("service[ip=10.0.0.1]");
+    assert_eq!(node.location(), Some("/service/0".to_string()));
+    let _ = node / "tls" << true;
+    assert_eq!(v["service"][0]["tls"], true);
+    let _ = v.path_mut() / "service[name=80]" / "name" << "serv_3";
+    assert_eq!(v["service"][2]["name"], "serv_3");
+    assert_eq!(v.pathto_mut("service[name=none]/port").is_none(), true);
+}
+
+#[test]
+fn embedded_json_test() {
+    //! test navigating into json text in string node.
+    use json_ops::{EmbedCache, EmbeddedMut, Pointer, PathSyntax, Wildcard, Glob};
+    let mut v = json!({"id": 1, "payload": "{\"a\": 1, \"list\": [{\"b\": \"[10, 20]\"}]}", "text": "not json", "num": "42"});
+
+    assert_eq!((v.path() / "payload" / "a").is_none(), true);
+    let cache = EmbedCache::new();
+    let trail = json_ops::PathTrail::new();
+    let root = v.path().with_embedded(&cache).with_trail(&trail);
+    assert_eq!(root / "payload" / "a" | 0, 1);
+    assert_eq!(root / "payload" / "list" / 0 / "b" / 1 | 0, 20);
+    assert_eq!(root.pathto("payload/list/0/b/-1") | 0, 20);
+    assert_eq!(root / "payload/list/0/b/0" | 0, 10);
+    assert_eq!(root / "payload" | "", "{\"a\": 1, \"list\": [{\"b\": \"[10, 20]\"}]}");
+    assert_eq!((root / "payload" / "a").location(), None);
+    assert_eq!((root / "payload" / "x").error().unwrap().suggestions, vec!["a"]);
+    assert_eq!((root / "text" / "a").is_none(), true);
+    assert_eq!((root / "num" / 0).is_none(), true);
+    // parsed only once, so the same node
+    assert_eq!(std::ptr::eq((root / "payload" / "a").unwrap(), (root / "payload" / "a").unwrap()), true);
+
+    let _ = EmbeddedMut::new(&mut v).path() / "payload" / "a" << 2;
+    assert_eq!(v["payload"], "{\"a\":2,\"list\":[{\"b\":\"[10, 20]\"}]}");
+    {
+        let mut embed = EmbeddedMut::new(&mut v);
+        let _ = embed.pathto("payload/list/0/b/1") << 30;
+        let _ = embed.path() / "payload" << ("c", true);
+        let _ = embed.path() / "id" << 2;
+        let _ = embed.path() / "text" / "a" << 1;
+    }
+    assert_eq!(v["payload"], "{\"a\":2,\"c\":true,\"list\":[{\"b\":\"[10,30]\"}]}");
+    assert_eq!(v["id"], 2);
+    assert_eq!(v["text"], "not json");
+    let cache = EmbedCache::new();
+    assert_eq!(v.path().with_embedded(&cache) / "payload" / "list" / 0 / "b" / 1 | 0, 30);
+
+    // every syntax navigates into string node
+    let root = v.path().with_embedded(&cache);
+    assert_eq!(root / &Pointer::new("/payload/list/0/b/1") | 0, 30);
+    assert_eq!(root.pathto_with("payload.list[0].b[-1]", PathSyntax::Dotted).unwrap() | 0, 30);
+    assert_eq!((root / "payload" / Wildcard).len(), 3);
+    assert_eq!((root / "payload" / "list" / (0..1)).len(), 1);
+    assert_eq!((root / "payload" / Glob("l*")).len(), 1);
+    assert_eq!(root / "payload" / "list" / Wildcard / "b" / 1 | 0, vec![30]);
+    {
+        let mut embed = EmbeddedMut::new(&mut v);
+        let _ = embed.path() / &Pointer::new("/payload/a") << 3;
+        let _ = embed.path().pathto_with("payload.list[0].b[0]", PathSyntax::Dotted).unwrap() << 0;
+    }
+    assert_eq!(v["payload"], "{\"a\":3,\"c\":true,\"list\":[{\"b\":\"[0,30]\"}]}");
+
+    // read only keeps the original text, and edit of array keeps the path
+    let mut v = json!({"l": ["{\"a\": 1}", "x"], "p": "{ \"b\" : [1, 2] }"});
+    {
+        let mut embed = EmbeddedMut::new(&mut v);
+        assert_eq!(embed.path() / "p" / "b" / 1 | 0, 2);
+        let _ = embed.path() / "l" / 0 / "a" << 2;
+        assert!((embed.path() / "l").insert_at(0, "y"));
+        let _ = embed.path() / "l" / 1 / "a" << 3;
+        assert_eq!((embed.path() / "l").remove_at(2), Some(json!("x")));
+    }
+    assert_eq!(v, json!({"l": ["y", "{\"a\":3}"], "p": "{ \"b\" : [1, 2] }"}));
+}
+
+#[test]
+fn mounted_tree_test() {
+    //! test viewing trees mounted under base tree.
+    use json_ops::{MountedTree, NodeKind};
+    let base = json!({"name": "app", "list": [1, 2], "plugins": {"bar": {"on": false}, "foo": "shadowed"}});
+    let foo = json!({"on": true, "port": "80", "args": ["-v", "-q"]});
+    let deep = json!({"level": 3});
+    let tree = MountedTree::new(&base)
+        .mount("/plugins/foo", &foo)
+        .mount("extra/a~1b/deep", &deep);
+
+    assert_eq!(tree.path() / "name" | "", "app");
+    assert_eq!(tree.path() / "list" / -1 | 0, 2);
+    assert_eq!(tree.pathto("list/0") | 0, 1);
+    assert_eq!(tree.path() / "plugins" / "foo" / "port" | 0, 80);
+    assert_eq!(tree.path() / "plugins" / "foo" / "args" / 1 | "", "-q");
+    assert_eq!(tree.pathto("/plugins/foo/args/0") | "", "-v");
+    assert_eq!(tree.pathto("plugins.bar.on") | true, false);
+    assert_eq!((tree.path() / "plugins" / "foo").kind(), Some(NodeKind::Object));
+    assert_eq!((tree.path() / "plugins").keys(), vec!["bar", "foo"]);
+    assert_eq!(tree.path().keys(), vec!["list", "name", "plugins", "extra"]);
+
+    let extra = tree.path() / "extra";
+    assert_eq!(extra.kind(), Some(NodeKind::Object));
+    assert_eq!(extra.clone() | "?".to_string(), "?");
+    assert_eq!(extra.keys(), vec!["a/b"]);
+    assert_eq!(extra.clone() / "a/b" / "deep" / "level" | 0, 3);
+    assert_eq!(tree.pathto("extra/a~1b/deep/level") | 0, 3);
+    assert_eq!((extra / "other").is_none(), true);
+    assert_eq!((tree.path() / "plugins" / "foo" / "name").is_none(), true);
+    assert_eq!((tree.path() / "nothing" / "deep").is_none(), true);
+
+    let tree = MountedTree::new(&base).mount("/", &foo);
+    assert_eq!(tree.path() / "port" | 0, 80);
+    assert_eq!((tree.path() / "name").is_none(), true);
+
+    // joined path in the syntax of `pathto()`, with predicate
+    let base = json!({"service": [{"name": "a", "ip": "1.2.3.4"}, {"name": "b", "ip": "5.6.7.8"}]});
+    let hosts = json!([{"host": "h1", "port": 80}, {"host": "h2", "port": 81}]);
+    let tree = MountedTree::new(&base).mount("/extra/hosts", &hosts);
+    assert_eq!(tree.path() / "service[name=b]/ip" | "", "5.6.7.8");
+    assert_eq!(tree.pathto("service[ip=1.2.3.4].name") | "", "a");
+    assert_eq!(tree.path() / "service" / "[name=b]" / "ip" | "", "5.6.7.8");
+    assert_eq!(tree.pathto("extra/hosts[host=h2]/port") | 0, 81);
+    assert_eq!(tree.pathto("extra/hosts/-1/host") | "", "h2");
+    assert_eq!(tree.pathto("service[name=c]/ip").is_none(), true);
+}
+
+#[test]
+fn entry_mode_test() {
+    //! test creating missing nodes in entry mode.
+    use json_ops::{Pointer, PathSyntax, EntryError, NodeKind};
+    let mut v = json!({});
+    let _ = v.path_create() / "a" / "b" / "c" << 1;
+    assert_eq!(v, json!({"a": {"b": {"c": 1}}}));
+    let _ = v.path_create().pathto("/a/list/0/name") << "first";
+    let _ = v.path_create() / "a" / "list" / 2 << "third";
+    assert_eq!(v["a"]["list"], json!([{"name": "first"}, null, "third"]));
+    let _ = v.path_create() / "a" / "list" / -1 << "last";
+    assert_eq!(v["a"]["list"][2], "last");
+    let node = v.path_create().pathto("a/x.y/z");
+    assert_eq!(node.location(), Some("/a/x/y/z".to_string()));
+    let _ = node << true;
+    assert_eq!(v["a"]["x"], json!({"y": {"z": true}}));
+
+    // single operand after `/` is literal key, unless the joined path exists
+    let _ = v.path_create() / "a" / "example.com" / "port" << 80;
+    assert_eq!(v["a"]["example.com"], json!({"port": 80}));
+    let node = v.path_create() / "a" / "x.y";
+    assert_eq!(node.location(), Some("/a/x/y".to_string()));
+
+    // pointer and other syntax also create missing nodes
+    let _ = v.path_create() / &Pointer::new("/p/q/1") << 1;
+    let _ = v.path_create().pathto_with("/p/r~1s", PathSyntax::Pointer).unwrap() << 2;
+    let _ = v.path_create().pathto_with("p.t[0].u", PathSyntax::Dotted).unwrap() << 3;
+    assert_eq!(v["p"], json!({"q": [null, 1], "r/s": 2, "t": [{"u": 3}]}));
+    assert!(v.path_create().pathto_with("p.q.x", PathSyntax::Dotted).unwrap().is_none());
+    let _ = v.path_create() / "a" / "list" / "-" << (4,);
+    assert_eq!(v["a"]["list"][3], 4);
+
+    // existing nodes are kept, and scalar or mismatch type fails
+    let _ = v.path_create() / "a" / "b" / "d" << 2;
+    assert_eq!(v["a"]["b"], json!({"c": 1, "d": 2}));
+    assert!((v.path_create() / "a" / "b" / "c" / "d").is_none());
+    assert!((v.path_create() / "a" / "b" / 0).is_none());
+    assert!((v.path_create() / "a" / "list" / "key").is_none());
+    assert!((v.path_create() / "a" / "list" / -9).is_none());
+    assert_eq!(v["a"]["b"], json!({"c": 1, "d": 2}));
+
+    // numeric key in non-empty object, and normal mode creates nothing
+    let _ = v.path_create().pathto("a/b/0") << "zero";
+    assert_eq!(v["a"]["b"]["0"], "zero");
+    assert!((v.path_mut() / "new" / "key").is_none());
+    assert!(v.get("new").is_none());
+
+    // index too far beyond the end fails, rather than overflow or allocate
+    let mut f = json!({"a": [1]});
+    assert!((f.path_create() / "a" / usize::MAX).is_none());
+    assert!(f.path_create().pathto("a/18446744073709551615").is_none());
+    assert!((f.path_create() / "a" / 2000).is_none());
+    assert_eq!(f["a"], json!([1]));
+    let _ = f.path_create() / "a" / 1024 << 2;
+    assert_eq!(f["a"].as_array().map(|a| a.len()), Some(1025));
+
+    // entry error tells why, and nodes created before the failure are left
+    let err = (f.path_create() / "a" / 3000).entry_error().cloned();
+    assert_eq!(err, Some(EntryError::TooFar { index: 3000, len: 1025, limit: 1024 }));
+    let mut n = json!(null);
+    let node = n.path_create() / "a" / 5000 << 1;
+    assert_eq!(node.entry_error(), Some(&EntryError::TooFar { index: 5000, len: 0, limit: 1024 }));
+    assert_eq!(n, json!({"a": null}));
+    let node = v.path_create() / "a" / "b" / "c" / "d" / "e";
+    assert_eq!(node.entry_error(), Some(&EntryError::Mismatch { token: "d".to_string(), found: NodeKind::Number }));
+    assert_eq!(node.entry_error().unwrap().to_string(), "cannot create `d` in number node");
+    assert_eq!((v.path_create() / "a" / "b").entry_error(), None);
+
+    // existing empty object is kept, only blank node becomes array
+    let mut e = json!({"m": {}, "n": null});
+    assert!((e.path_mut().entry() / "m" / 1).is_none());
+    let _ = e.path_mut().entry().pathto("m/1") << 1;
+    let _ = e.path_mut().entry() / "n" / 1 << 1;
+    assert_eq!(e, json!({"m": {"1": 1}, "n": [null, 1]}));
+}
+
+#[test]
+fn remove_test() {
+    //! test removing child through mutable pointer.
+    use json_ops::{KeyMatcher, ValueWriter};
+    let mut v = json!({"host": {"ip": "127.0.0.1", "port": 80, "Max-Conns": 10}, "list": [1, 2, 3, 4], "s": "str"});
+
+    assert_eq!(v.path_mut() / "host" >> "port", Some(json!(80)));
+    assert_eq!(v.path_mut() / "host" >> "port", None);
+    assert_eq!(v.path_mut().with_matcher(KeyMatcher::Normalized) / "HOST" >> "max_conns", Some(json!(10)));
+    assert_eq!(v["host"], json!({"ip": "127.0.0.1"}));
+    assert_eq!(v.path_mut() / "list" >> 1, Some(json!(2)));
+    assert_eq!(v.path_mut() / "list" >> -1, Some(json!(4)));
+    assert_eq!(v.path_mut() / "list" >> 5, None);
+    assert_eq!(v.path_mut() / "list" >> "0".to_string(), Some(json!(1)));
+    assert_eq!(v["list"], json!([3]));
+    assert_eq!(v.path_mut() / "s" >> "x", None);
+    assert_eq!(v.path_mut() / "none" >> "x", None);
+    assert_eq!(v.path_mut() >> "list", Some(json!([3])));
+
+    let mut host = v.path_mut() / "host";
+    assert_eq!(host.remove("ip"), Some(json!("127.0.0.1")));
+    assert_eq!(host.remove("ip"), None);
+    assert_eq!(v, json!({"host": {}, "s": "str"}));
+
+    assert_eq!(v.remove_key("s"), Some(json!("str")));
+    assert_eq!(v.remove_index(0), None);
+}
+
+#[test]
+fn array_edit_test() {
+    //! test positional insert, splice and pop on array.
+    let mut v = json!({"chain": ["auth", "log"], "obj": {"a": 1}});
+
+    let mut chain = v.path_mut() / "chain";
+    assert_eq!(chain.insert_at(1, "cors"), true);
+    assert_eq!(chain.insert_at(-3, "trace"), true);
+    assert_eq!(chain.insert_at(4, 5), true);
+    assert_eq!(chain.insert_at(6, "far"), false);
+    assert_eq!(chain.insert_at(-6, "far"), false);
+    assert_eq!(v["chain"], json!(["trace", "auth", "cors", "log", 5]));
+
+    let mut chain = v.path_mut() / "chain";
+    assert_eq!(chain.splice(1..=2, ["a", "b", "c"]), Some(vec![json!("auth"), json!("cors")]));
+    assert_eq!(chain.splice(9.., [1]), Some(vec![]));
+    assert_eq!(chain.splice(..0, vec![json!({"x": 0})]), Some(vec![]));
+    assert_eq!(chain.pop(), Some(json!(1)));
+    assert_eq!(chain.pop(), Some(json!(5)));
+    assert_eq!(v["chain"], json!([{"x": 0}, "trace", "a", "b", "c", "log"]));
+
+    let mut obj = v.path_mut() / "obj";
+    assert_eq!(obj.insert_at(0, 1), false);
+    assert_eq!(obj.splice(.., [1]), None);
+    assert_eq!(obj.pop(), None);
+    assert_eq!((v.path_mut() / "none").pop(), None);
+    assert_eq!(v["obj"], json!({"a": 1}));
+
+    let mut empty = json!([]);
+    assert_eq!(empty.path_mut().pop(), None);
+    assert_eq!(empty.path_mut().insert_at(0, true), true);
+    assert_eq!(empty, json!([true]));
+}
+
+#[test]
+fn patch_test() {
+    //! test json patch apply all or nothing.
+    use json_ops::{apply_patch, Patch, PatchOp, PatchErrorKind};
+    let mut v = json!({"foo": {"bar": [1, 2], "a/b": 0}, "baz": "qux"});
+
+    let patch = json!([
+        {"op": "add", "path": "/foo/bar/1", "value": 9},
+        {"op": "add", "path": "/foo/new", "value": {"x": null}},
+        {"op": "remove", "path": "/foo/a~1b"},
+        {"op": "copy", "from": "/foo/bar", "path": "/bar"},
+        {"op": "move", "from": "/baz", "path": "/bar/0"},
+        {"op": "test", "path": "/bar", "value": ["qux", 1.0, 9, 2]},
+        {"op": "replace", "path": "/foo/new/x", "value": true},
+        {"op": "move", "from": "/foo", "path": "/foo"},
+    ]);
+    assert_eq!(apply_patch(&mut v, &patch), Ok(()));
+    assert_eq!(v, json!({"foo": {"bar": [1, 9, 2], "new": {"x": true}}, "bar": ["qux", 1, 9, 2]}));
+
+    let snapshot = v.clone();
+    let fails = vec![
+        (json!({"op": "remove", "path": "/none"}), PatchErrorKind::NotFound),
+        (json!({"op": "add", "path": "/foo/bar/4", "value": 0}), PatchErrorKind::NotFound),
+        (json!({"op": "add", "path": "/foo/bar/01", "value": 0}), PatchErrorKind::NotFound),
+        (json!({"op": "add", "path": "/none/x", "value": 0}), PatchErrorKind::NotFound),
+        (json!({"op": "replace", "path": "foo", "value": 0}), PatchErrorKind::InvalidPath),
+        (json!({"op": "test", "path": "/foo/bar/0", "value": "1"}), PatchErrorKind::TestFailed),
+        (json!({"op": "move", "from": "/foo", "path": "/foo/new/foo"}), PatchErrorKind::MoveIntoChild),
+        (json!({"op": "copy", "path": "/x"}), PatchErrorKind::Malformed("missing `from` string")),
+        (json!({"op": "push", "path": "/x"}), PatchErrorKind::Malformed("unknown `op`")),
+    ];
+    for (op, kind) in fails {
+        let patch = json!([{"op": "remove", "path": "/bar"}, op]);
+        let err = apply_patch(&mut v, &patch).unwrap_err();
+        assert_eq!((err.op, err.kind), (1, kind));
+        assert_eq!(v, snapshot);
+    }
+    let mut e = json!({"a": [], "o": {}});
+    let err = apply_patch(&mut e, &json!([{"op": "test", "path": "/o", "value": []}])).unwrap_err();
+    assert_eq!(err.kind, PatchErrorKind::TestFailed);
+    assert_eq!(apply_patch(&mut e, &json!([{"op": "test", "path": "/a", "value": []}])), Ok(()));
+    let err = apply_patch(&mut v, &json!([{"op": "remove", "path": "/bar/9"}])).unwrap_err();
+    assert_eq!(err.to_string(), "patch op 0 at `/bar/9`: path not found");
+
+    let ops = Patch(vec![
+        PatchOp::Remove { path: "/foo".to_string() },
+        PatchOp::Add { path: "".to_string(), value: json!([]) },
+        PatchOp::Add { path: "/-".to_string(), value: json!("end") },
+    ]);
+    assert_eq!(ops.0[2].name(), "add");
+    assert_eq!(apply_patch(&mut v, &ops), Ok(()));
+    assert_eq!(v, json!(["end"]));
+    assert_eq!(Patch::<serde_json::Value>::from_json(&json!({})).unwrap_err().kind, PatchErrorKind::Malformed("patch is not array"));
+}
+
+#[test]
+fn diff_test() {
+    //! test diff then apply patch turns old to new.
+    use json_ops::{diff, diff_with, apply_patch, ArrayDiff, Patch};
+    let old = json!({"name": "app", "list": [1, 2, 3], "obj": {"x": [{"a": 1}, {"b": 2}]}});
+    assert_eq!(diff(&old, &old).0.len(), 0);
+
+    let new = json!({"name": ["app"], "list": [1, 3], "obj": {"x": [{"a": 1}, {"b": 3}], "~y": null}});
+    let patch = diff(&old, &new);
+    assert_eq!(patch.to_json(), json!([
+        {"op": "replace", "path": "/list/1", "value": 3},
+        {"op": "remove", "path": "/list/2"},
+        {"op": "replace", "path": "/name", "value": ["app"]},
+        {"op": "replace", "path": "/obj/x/1/b", "value": 3},
+        {"op": "add", "path": "/obj/~0y", "value": null},
+    ]));
+    let patch = diff_with(&old, &new, ArrayDiff::Lcs);
+    assert_eq!(patch.to_json(), json!([
+        {"op": "remove", "path": "/list/1"},
+        {"op": "replace", "path": "/name", "value": ["app"]},
+        {"op": "replace", "path": "/obj/x/1/b", "value": 3},
+        {"op": "add", "path": "/obj/~0y", "value": null},
+    ]));
+    assert_eq!(Patch::from_json(&patch.to_json()), Ok(patch));
+
+    let arrays = [
+        json!([]),
+        json!([1, 2, 3, 4, 5]),
+        json!([5, 4, 3, 2, 1]),
+        json!([2, 1, 4, 3, 6]),
+        json!([1, [2], {"x": 3}, 1, 5]),
+        json!([{"x": 4}, 1, 1, 7, [2]]),
+        json!([3, 3, 0, 5, 1, 2]),
+    ];
+    for old in arrays.iter() {
+        for new in arrays.iter() {
+            for mode in [ArrayDiff::Index, ArrayDiff::Lcs] {
+                let mut v = json!({"a": old});
+                let patch = diff_with(&v, &json!({"a": new}), mode);
+                assert_eq!(apply_patch(&mut v, &patch), Ok(()));
+                assert_eq!(v, json!({"a": new}), "{} -> {} by {:?}", old, new, mode);
+            }
+        }
+    }
+
+    let old = json!(["a", "b", "c", "d", "e"]);
+    let new = json!(["b", "e", "c", "d", "a"]);
+    assert_eq!(diff_with(&old, &new, ArrayDiff::Lcs).to_json(), json!([
+        {"op": "move", "from": "/4", "path": "/2"},
+        {"op": "move", "from": "/0", "path": "/4"},
+    ]));
+}
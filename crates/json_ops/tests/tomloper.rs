@@ -321,3 +321,440 @@ fn path_if_test() {
 }
 
 
+
+#[test]
+fn query_test() {
+    let v = load_test_toml();
+
+    let names = v.query("$.service[*].name").unwrap();
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0].as_str(), Some("serv_1"));
+    assert_eq!(names[1].as_str(), Some("serv_2"));
+
+    let ip = v.query("$..ip").unwrap();
+    assert_eq!(ip.len(), 2);
+
+    let desc = v.query("$.service[?@.name == 'serv_2'].desc").unwrap();
+    assert_eq!(desc.len(), 1);
+    assert_eq!(desc[0].as_str(), Some("another server"));
+
+    let host = v.query("$[?@.port > 8000].ip").unwrap();
+    assert_eq!(host.len(), 1);
+    assert_eq!(host[0].as_str(), Some("127.0.1.1"));
+
+    let proto = v.query("$.host.protocol[-2:]").unwrap();
+    assert_eq!(proto.len(), 2);
+    assert_eq!(proto[1].as_str(), Some("mmp"));
+
+    assert!(v.query("$.misc[?@ == 3.14]").unwrap().len() == 1);
+}
+
+#[test]
+fn wildcard_test() {
+    use json_ops::Wildcard;
+    let v = load_test_toml();
+
+    let names = v.path() / "service" / Wildcard / "name";
+    assert_eq!(names.len(), 2);
+    assert_eq!(names | "", vec!["serv_1", "serv_2"]);
+
+    let desc = v.path() / "service" / Wildcard / "desc" | "".to_string();
+    assert_eq!(desc, vec!["first server", "another server"]);
+
+    let ips = v.path() / Wildcard / "ip";
+    assert_eq!(ips | "", vec!["127.0.1.1"]);
+    let protos = v.path() / Wildcard / Wildcard / 1;
+    assert_eq!(protos | "", vec!["udp"]);
+
+    // "*" is literal key for both single pointer and pointer set
+    let node = v.path() / "service" / "*";
+    assert!(node.is_none());
+    assert!((v.path() / Wildcard / "*").is_empty());
+
+    let misc = v.path() / "misc" / Wildcard;
+    assert_eq!(misc.len(), 3);
+    for node in misc.iter() {
+        assert!(node.is_some());
+    }
+    assert_eq!(misc.into_iter().count(), 3);
+
+    let none = v.path() / "nokey" / Wildcard / "name";
+    assert!(none.is_empty());
+}
+
+#[test]
+fn path_negative_test() {
+    let mut v = load_test_toml();
+
+    assert_eq!(v.path() / "host" / "protocol" / -1 | "", "mmp");
+    assert_eq!(v.pathto("service/-1/name") | "", "serv_2");
+    assert_eq!(v.pathto("service.-2.name") | "", "serv_1");
+    assert_eq!(v.path() / "service" / -3 | "", "");
+
+    let _ = v.path_mut() / "host" / "protocol" / "-" << "quic";
+    assert_eq!(v.path() / "host" / "protocol" / 3 | "", "quic");
+    let _ = v.pathto_mut("host/protocol/-") << 8080;
+    assert_eq!(v.path() / "host" / "protocol" / -1 | 0, 8080);
+    assert_eq!(v.path() / "host" / "protocol" / -5 | "", "tcp");
+}
+
+#[test]
+fn path_range_test() {
+    let mut v = load_test_toml();
+
+    let proto = v.path() / "host" / "protocol" / (0..2);
+    assert_eq!(proto.len(), 2);
+    assert_eq!(proto.clone() | "", vec!["tcp", "udp"]);
+    assert_eq!(proto.iter().count(), 2);
+    assert_eq!(v.path() / "host" / "protocol" / (1..) | "", vec!["udp", "mmp"]);
+    assert_eq!(v.path() / "host" / "protocol" / (..=0) | "", vec!["tcp"]);
+    let (start, end) = (2, 1);
+    assert_eq!((v.path() / "host" / "protocol" / (start..end)).len(), 0);
+    assert_eq!((v.path() / "host" / "protocol" / (5..)).len(), 0);
+    assert_eq!((v.path() / "host" / (..)).len(), 0);
+
+    let names = v.path() / "service" / (..) / "name";
+    assert_eq!(names | "", vec!["serv_1", "serv_2"]);
+
+    for node in v.path_mut() / "host" / "protocol" / (..2) {
+        let _ = node << "tls";
+    }
+    assert_eq!(v.path_mut() / "host" / "protocol" / (..) | "", vec!["tls", "tls", "mmp"]);
+}
+
+#[test]
+fn location_test() {
+    let mut v = load_test_toml();
+    let trail = json_ops::PathTrail::new();
+    let root = v.path().with_trail(&trail);
+
+    assert_eq!((root / "service" / 1 / "desc").location(), Some("/service/1/desc".to_string()));
+    assert_eq!(root.pathto("host.protocol.-1").location(), Some("/host/protocol/2".to_string()));
+    assert_eq!((root / "host" / "none").location(), None);
+    assert_eq!((v.path() / "host").location(), None);
+
+    let node = v.path_mut() / "host" / "protocol" / "-" << "quic";
+    assert_eq!(node.location(), Some("/host/protocol/3".to_string()));
+    assert_eq!(v.pathto_mut("service/0/name").location(), Some("/service/0/name".to_string()));
+}
+
+#[test]
+fn path_error_test() {
+    use json_ops::{PathErrorKind, NodeKind};
+    let v = load_test_toml();
+
+    let err = v.pathto("service.1.dsec").error().unwrap();
+    assert_eq!(err.segment, 2);
+    assert_eq!(err.kind, PathErrorKind::MissingKey);
+    assert_eq!(err.suggestions, vec!["desc"]);
+
+    let err = (v.path() / "service" / 2 / "name").error().unwrap();
+    assert_eq!(err.segment, 1);
+    assert_eq!(err.kind, PathErrorKind::IndexOutOfRange { len: 2 });
+
+    let err = (v.path() / "host" / "port" / "x").error().unwrap();
+    assert_eq!(err.found, NodeKind::Number);
+    assert_eq!(err.kind, PathErrorKind::NotContainer);
+}
+
+#[test]
+fn path_syntax_test() {
+    use json_ops::PathSyntax;
+    let mut v = load_test_toml();
+
+    assert_eq!(v.pathto_with("/service/1/name", PathSyntax::Pointer).unwrap() | "", "serv_2");
+    assert_eq!(v.pathto_with("service[1].name", PathSyntax::Dotted).unwrap() | "", "serv_2");
+    assert_eq!(v.pathto_with("host['protocol'][-1]", PathSyntax::Bracket).unwrap() | "", "mmp");
+    assert_eq!(v.pathto_with("host.protocol.0", PathSyntax::Dotted).unwrap().is_none(), true);
+
+    let _ = v.pathto_mut_with("/host/protocol/-", PathSyntax::Pointer).unwrap() << "quic";
+    assert_eq!(v.path() / "host" / "protocol" / 3 | "", "quic");
+}
+
+#[test]
+fn pointer_type_test() {
+    use json_ops::Pointer;
+    static PROTO: Pointer = Pointer::from_static(&["host", "protocol"], &[None, None]);
+    let mut v = load_test_toml();
+
+    let name = Pointer::new("service.1.name");
+    assert_eq!(v.path() / &name | "", "serv_2");
+    assert_eq!(v.path() / &PROTO / -1 | "", "mmp");
+    let _ = PROTO.resolve_mut(&mut v) << ("quic",);
+    assert_eq!(PROTO.resolve(&v) / 3 | "", "quic");
+}
+
+#[test]
+fn path_macro_test() {
+    use json_ops::path;
+    let v = load_test_toml();
+
+    assert_eq!(v.path() / path!("service/1/name") | "", "serv_2");
+    assert_eq!(v.path() / path!("host.protocol.-1") | "", "mmp");
+}
+
+#[test]
+fn cursor_test() {
+    let v = load_test_toml();
+
+    let trail = json_ops::PathTrail::new();
+    let name = v.path().with_trail(&trail).pathto("service/0/name").cursor().unwrap();
+    assert_eq!(name.depth(), 3);
+    let serv = name.parent().unwrap();
+    assert_eq!(serv.index(), Some(0));
+    let next = serv.next_sibling().unwrap();
+    assert_eq!(next.child("name").unwrap().ptr() | "", "serv_2");
+    assert_eq!(next.parent().unwrap().key(), Some("service"));
+    assert_eq!(name.root().child("host").unwrap().child("port").unwrap().ptr() | 0, 8080);
+}
+
+#[test]
+fn relative_test() {
+    use json_ops::{Relative, RelativeError, PathSyntax, PathTrail};
+    let mut v = load_test_toml();
+
+    let trail = PathTrail::new();
+    let desc = v.path().with_trail(&trail).pathto("service/1/desc");
+    assert_eq!(desc.relative("0#").unwrap(), Relative::Key("desc".to_string()));
+    assert_eq!(desc.relative("1#").unwrap(), Relative::Index(1));
+    assert_eq!(desc.relative("1/name").unwrap().ptr().unwrap() | "", "serv_2");
+    assert_eq!(desc.relative("1-1/name").unwrap().ptr().unwrap() | "", "serv_1");
+    assert_eq!(desc.relative("3/host/port").unwrap().ptr().unwrap() | 0, 8080);
+
+    assert_eq!(desc.relative("4#"), Err(RelativeError::AboveRoot));
+
+    let mut serv = v.pathto_mut("service/1");
+    assert_eq!(serv.relative("0#").unwrap(), Relative::Index(1));
+    let err = serv.relative("0-1/name").unwrap_err();
+    assert_eq!(err, RelativeError::Upward { pointer: "/service/0/name".to_string() });
+    let _ = serv.relative("0/name").unwrap().ptr().unwrap() << "serv_two";
+    assert_eq!(v.pathto("service/1/name") | "", "serv_two");
+    let _ = v.pathto_mut_with("/service/0/name", PathSyntax::Pointer).unwrap() << "serv_one";
+    assert_eq!(v.pathto("service/0/name") | "", "serv_one");
+}
+
+#[test]
+fn find_paths_test() {
+    use json_ops::{find_paths, Needle};
+    let v = load_test_toml();
+
+    assert_eq!(find_paths(&v, Needle::Equal(&Value::from("serv_2"))), vec!["/service/1/name"]);
+    assert_eq!(find_paths(&v, Needle::Equal(&Value::from(8080))), vec!["/host/port"]);
+    let proto = v.pathto("host/protocol/1").unwrap();
+    assert_eq!(find_paths(&v, Needle::Node(proto)), vec!["/host/protocol/1"]);
+}
+
+#[test]
+fn key_matcher_test() {
+    use json_ops::KeyMatcher;
+    let mut v = load_test_toml();
+
+    let trail = json_ops::PathTrail::new();
+    let root = v.path().with_trail(&trail).with_matcher(KeyMatcher::IgnoreCase);
+    assert_eq!(root / "Host" / "Port" | 0, 8080);
+    assert_eq!((root / "Host" / "Port").matched_key(), Some("port"));
+    assert_eq!(root.pathto("SERVICE.0.Name") | "", "serv_1");
+
+    let _ = v.path_mut().with_matcher(KeyMatcher::Normalized) / "HOST" / "_port_" << 9090;
+    assert_eq!(v.path() / "host" / "port" | 0, 9090);
+}
+
+#[test]
+fn glob_key_test() {
+    use json_ops::Glob;
+    let v = load_test_toml();
+
+    assert_eq!(v.path() / "host" / Glob("p*") | 0, vec![8080, 0]);
+    assert_eq!((v.path() / "host" / Glob("p*")).len(), 2);
+    assert_eq!(v.path() / "service" / json_ops::Wildcard / Glob("[n]am?") | "", vec!["serv_1", "serv_2"]);
+}
+
+#[test]
+fn descendants_test() {
+    let v = load_test_toml();
+
+    let names: Vec<_> = v.path().descendants_named("name").map(|(path, node)| (path, node.as_str().unwrap())).collect();
+    assert_eq!(names[0], ("/service/0/name".to_string(), "serv_1"));
+    assert_eq!(names.len(), 2);
+    let protocols: Vec<_> = v.path().descendants_where(|path, _| path.starts_with("/host/protocol/")).map(|(path, _)| path).collect();
+    assert_eq!(protocols, vec!["/host/protocol/0", "/host/protocol/1", "/host/protocol/2"]);
+}
+
+#[test]
+fn fallback_test() {
+    let v = load_test_toml();
+
+    assert_eq!(v.path() / "host" / "ip" | v.path() / "ip" | "0.0.0.0", "127.0.1.1");
+    assert_eq!(v.path() / "misc" / "ip" | v.path() / "ip" | "0.0.0.0", "127.0.0.1");
+    assert_eq!((v.path() / "service" / 0 / "port").or(v.path() / "host" / "port") | 0, 8080);
+    assert_eq!(v.path() / "service" / 1 / "port" | v.path() / "misc" / "port" | 80, 80);
+}
+
+#[test]
+fn layered_test() {
+    use json_ops::LayeredPtr;
+    let v = load_test_toml();
+    let user: Value = "[host]\nport = 9090\n[misc]\nint = 1\n".parse().unwrap();
+    let conf = LayeredPtr::new(vec![&v, &user]);
+
+    assert_eq!(conf.clone() / "host" / "port" | 0, 9090);
+    assert_eq!(conf.clone() / "host" / "ip" | "", "127.0.1.1");
+    assert_eq!((conf.clone() / "misc" / "int").origin(), Some(1));
+    assert_eq!((conf.clone() / "misc" / "float").origin(), Some(0));
+    assert_eq!(conf / "service" / 1 / "name" | "", "serv_2");
+}
+
+#[test]
+fn predicate_test() {
+    let mut v = load_test_toml();
+
+    assert_eq!(v.pathto("service[name=serv_2]/desc") | "", "another server");
+    assert_eq!(v.pathto("service[desc=first server].name") | "", "serv_1");
+    let _ = v.pathto_mut("service[name=serv_2]/desc") << "second server";
+    assert_eq!(v.pathto("service/1/desc") | "", "second server");
+}
+
+#[test]
+fn mounted_tree_test() {
+    use json_ops::MountedTree;
+    let v = load_test_toml();
+    let base = serde_json::json!({"app": {"name": "demo"}});
+    let tree = MountedTree::new(&base).mount("/app/conf", &v);
+
+    assert_eq!(tree.path() / "app" / "name" | "", "demo");
+    assert_eq!(tree.path() / "app" / "conf" / "host" / "port" | 0, 8080);
+    assert_eq!(tree.pathto("app/conf/service/1/name") | "", "serv_2");
+    assert_eq!(tree.pathto("app/conf/misc/float") | 0.0, 3.14);
+    assert_eq!((tree.path() / "app").keys(), vec!["name", "conf"]);
+}
+
+#[test]
+fn entry_mode_test() {
+    let mut v = load_test_toml();
+
+    let _ = v.path_create() / "host" / "tls" / "cert" << "a.pem";
+    assert_eq!(v.pathto("host/tls/cert") | "", "a.pem");
+    let _ = v.path_create().pathto("service/2/name") << "serv_3";
+    assert_eq!(v.pathto("service/2/name") | "", "serv_3");
+    let _ = v.path_create() / "new" / "list" / 1 << 10;
+    assert_eq!(v.pathto("new/list/1") | 0, 10);
+    assert_eq!(v.pathto("new/list/0").unwrap().as_table().map(|t| t.is_empty()), Some(true));
+    assert!((v.path_create() / "host" / "port" / "x").is_none());
+    assert!((v.path_create() / "host" / "protocol" / "x").is_none());
+    assert!((v.path_create() / "host" / "protocol" / usize::MAX).is_none());
+    assert!(v.path_create().pathto("host/protocol/18446744073709551615").is_none());
+    assert_eq!(v.pathto("host/protocol").unwrap().as_array().map(|a| a.len()), Some(3));
+
+    // existing empty table is kept, while the one just created becomes array
+    let _ = v.path_mut() / "host" << ("empty", Value::Table(toml::Table::new()));
+    assert!((v.path_create() / "host" / "empty" / 0).is_none());
+    let _ = v.path_create().pathto("host/empty/0") << 1;
+    assert_eq!(v.pathto("host/empty/0") | 0, 1);
+    assert!(v.pathto("host/empty").unwrap().is_table());
+}
+
+#[test]
+fn remove_test() {
+    let mut v = load_test_toml();
+
+    assert_eq!(v.path_mut() / "host" >> "port", Some(Value::from(8080)));
+    assert_eq!((v.path() / "host" / "port").is_none(), true);
+    assert_eq!(v.path_mut() / "host" / "protocol" >> -1, Some(Value::from("mmp")));
+    assert_eq!((v.path_mut() / "host" / "protocol").remove("0"), Some(Value::from("tcp")));
+    assert_eq!(v.pathto("host/protocol/0") | "", "udp");
+    let serv = v.path_mut() / "service" >> 0;
+    assert_eq!(serv.unwrap().get("name").unwrap().as_str(), Some("serv_1"));
+    assert_eq!(v.pathto("service/0/name") | "", "serv_2");
+    let misc = v.get("misc").cloned();
+    assert_eq!(v.path_mut() >> "misc", misc);
+    assert_eq!((v.path() / "misc").is_none(), true);
+}
+
+#[test]
+fn array_edit_test() {
+    let mut v = load_test_toml();
+
+    let mut protocol = v.path_mut() / "host" / "protocol";
+    assert_eq!(protocol.insert_at(0, "http"), true);
+    assert_eq!(protocol.pop(), Some(Value::from("mmp")));
+    assert_eq!(protocol.splice(1..2, ["quic"]), Some(vec![Value::from("tcp")]));
+    assert_eq!(v.pathto("host/protocol/0") | "", "http");
+    assert_eq!(v.pathto("host/protocol/1") | "", "quic");
+    assert_eq!(v.pathto("host/protocol/2") | "", "udp");
+    assert_eq!(v.path() / "host" / "protocol" / 3 | "", "");
+    assert_eq!((v.path_mut() / "host").pop(), None);
+}
+
+#[test]
+fn patch_test() {
+    use json_ops::{apply_patch, PatchErrorKind};
+    use serde_json::json;
+    let mut v = load_test_toml();
+
+    let patch = json!([
+        {"op": "test", "path": "/host/port", "value": 8080},
+        {"op": "replace", "path": "/host/port", "value": 9090},
+        {"op": "add", "path": "/host/protocol/0", "value": "http"},
+        {"op": "remove", "path": "/service/1"},
+        {"op": "add", "path": "/misc/limits", "value": {"conns": 100, "ratio": 0.5}},
+        {"op": "move", "from": "/misc/bool", "path": "/host/enable"},
+    ]);
+    assert_eq!(apply_patch(&mut v, &patch), Ok(()));
+    assert_eq!(v.pathto("host/port") | 0, 9090);
+    assert_eq!(v.pathto("host/protocol/0") | "", "http");
+    assert_eq!(v.pathto("host/protocol").unwrap().as_array().unwrap().len(), 4);
+    assert_eq!(v.pathto("service").unwrap().as_array().unwrap().len(), 1);
+    assert_eq!(v.pathto("misc/limits/conns") | 0, 100);
+    assert_eq!(v.pathto("host/enable") | false, true);
+    assert_eq!(v.pathto("misc/bool").is_none(), true);
+
+    let snapshot = v.clone();
+    let patch = json!([
+        {"op": "remove", "path": "/ip"},
+        {"op": "add", "path": "/host/none", "value": null},
+    ]);
+    let err = apply_patch(&mut v, &patch).unwrap_err();
+    assert_eq!((err.op, err.kind), (1, PatchErrorKind::Unsupported));
+    assert_eq!(v, snapshot);
+
+    let patch = json!([
+        {"op": "add", "path": "/misc/empty", "value": {}},
+        {"op": "test", "path": "/misc/empty", "value": []},
+    ]);
+    let err = apply_patch(&mut v, &patch).unwrap_err();
+    assert_eq!((err.op, err.kind), (1, PatchErrorKind::TestFailed));
+    assert_eq!(v, snapshot);
+}
+
+#[test]
+fn diff_test() {
+    use json_ops::{diff, diff_with, apply_patch, ArrayDiff};
+    use serde_json::json;
+    let old = load_test_toml();
+    let mut new = old.clone();
+    let patch = json!([
+        {"op": "replace", "path": "/host/port", "value": 9090},
+        {"op": "move", "from": "/host/protocol/2", "path": "/host/protocol/0"},
+        {"op": "remove", "path": "/misc/float"},
+        {"op": "add", "path": "/service/0/port", "value": 80},
+    ]);
+    assert_eq!(apply_patch(&mut new, &patch), Ok(()));
+
+    let index = diff(&old, &new);
+    assert_eq!(index.to_json(), json!([
+        {"op": "replace", "path": "/host/port", "value": 9090},
+        {"op": "replace", "path": "/host/protocol/0", "value": "mmp"},
+        {"op": "replace", "path": "/host/protocol/1", "value": "tcp"},
+        {"op": "replace", "path": "/host/protocol/2", "value": "udp"},
+        {"op": "remove", "path": "/misc/float"},
+        {"op": "add", "path": "/service/0/port", "value": 80},
+    ]));
+    let lcs = diff_with(&old, &new, ArrayDiff::Lcs);
+    assert_eq!(lcs.to_json(), patch);
+
+    for patch in [index, lcs] {
+        let mut v = old.clone();
+        assert_eq!(apply_patch(&mut v, &patch), Ok(()));
+        assert_eq!(v, new);
+    }
+}